@@ -0,0 +1,94 @@
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use xal::url::Url;
+use crate::error::{LauncherError, LauncherResult};
+
+/// How long the browser login may take before giving up
+const REDIRECT_TIMEOUT: Duration = Duration::from_secs(300);
+
+const SUCCESS_PAGE: &str = "<html><body style=\"font-family:sans-serif\"><h2>Login complete</h2><p>You can close this window and return to the launcher.</p></body></html>";
+const FAILURE_PAGE: &str = "<html><body style=\"font-family:sans-serif\"><h2>Login failed</h2><p>Return to the launcher and try again.</p></body></html>";
+
+/// Short-lived HTTP listener on 127.0.0.1 that catches the OAuth2 authorization-code redirect
+pub struct LoopbackListener {
+    listener: TcpListener,
+    port: u16,
+}
+
+impl LoopbackListener {
+    pub async fn bind() -> LauncherResult<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| LauncherError::io(format!("Failed to bind redirect listener: {}", e)))?;
+        let port = listener.local_addr()?.port();
+        println!("[Auth] Redirect listener on port {}", port);
+        Ok(Self { listener, port })
+    }
+
+    /// The redirect URI to register with the authorization request
+    pub fn redirect_uri(&self) -> String {
+        format!("http://localhost:{}", self.port)
+    }
+
+    /// Wait for the browser to hit the redirect URI and return the full URL it requested.
+    /// Requests without `code` or `error` (favicon, prefetches) are answered and ignored.
+    pub async fn wait_for_redirect(self) -> LauncherResult<Url> {
+        tokio::time::timeout(REDIRECT_TIMEOUT, self.accept_redirect())
+            .await
+            .map_err(|_| LauncherError::Auth { code: "LOGIN_TIMEOUT".into(), message: "Timed out waiting for the browser login".into() })?
+    }
+
+    async fn accept_redirect(&self) -> LauncherResult<Url> {
+        loop {
+            let (mut stream, _) = self.listener.accept()
+                .await
+                .map_err(|e| LauncherError::io(format!("Redirect listener failed: {}", e)))?;
+
+            let mut buffer = [0u8; 8192];
+            let read = match stream.read(&mut buffer).await {
+                Ok(read) => read,
+                Err(_) => continue,
+            };
+            let head = String::from_utf8_lossy(&buffer[..read]).to_string();
+            let target = head.lines()
+                .next()
+                .and_then(|line| line.split_whitespace().nth(1))
+                .unwrap_or_default()
+                .to_string();
+
+            let url = match Url::parse(&format!("{}{}", self.redirect_uri(), target)) {
+                Ok(url) => url,
+                Err(_) => continue,
+            };
+            let has_code = url.query_pairs().any(|(k, _)| k == "code");
+            let has_error = url.query_pairs().any(|(k, _)| k == "error");
+
+            if !has_code && !has_error {
+                let _ = stream.write_all(&http_response(404, "")).await;
+                continue;
+            }
+
+            let page = if has_code { SUCCESS_PAGE } else { FAILURE_PAGE };
+            let _ = stream.write_all(&http_response(200, page)).await;
+            let _ = stream.shutdown().await;
+
+            if has_error {
+                let description = url.query_pairs()
+                    .find(|(k, _)| k == "error_description" || k == "error")
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default();
+                return Err(LauncherError::Auth { code: "AUTHORIZATION_DENIED".into(), message: format!("Authorization was denied: {}", description) });
+            }
+            return Ok(url);
+        }
+    }
+}
+
+fn http_response(status: u16, body: &str) -> Vec<u8> {
+    let reason = if status == 200 { "OK" } else { "Not Found" };
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason, body.len(), body
+    ).into_bytes()
+}
@@ -0,0 +1,414 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{Emitter, Window};
+use xal::{
+    app_params::MC_BEDROCK_SWITCH,
+    client_params::CLIENT_NINTENDO,
+    Flows, XalAuthenticator, XalAppParameters, TokenStore,
+    AuthPromptCallback, AuthPromptData,
+    AccessTokenPrefix
+};
+use crate::auth::{AuthError, AuthProvider, AuthResult, GameProfile, LoginRequest};
+use crate::auth::loopback::LoopbackListener;
+use crate::auth::vault;
+use crate::auth::services::{MinecraftServicesClient, MINECRAFT_SERVICES_URL};
+use crate::launcher::MinecraftLauncher;
+
+/// Azure application (client) id for the browser login, read at runtime and then at build time.
+/// The Nintendo Switch client the device code flow uses has no localhost redirect registered,
+/// so the browser flow needs an app registration of our own that allows loopback redirects
+/// (a "Mobile and desktop applications" platform with `http://localhost` as redirect URI).
+const BROWSER_CLIENT_ID_VAR: &str = "PORCOS_MS_CLIENT_ID";
+
+fn browser_client_id() -> Option<String> {
+    std::env::var(BROWSER_CLIENT_ID_VAR).ok()
+        .or_else(|| option_env!("PORCOS_MS_CLIENT_ID").map(str::to_string))
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// Live tokens of our own Azure app are sent as `d=<token>`; the Switch client's are sent bare
+fn token_prefix(app_params: &XalAppParameters) -> AccessTokenPrefix {
+    if app_params.client_id == MC_BEDROCK_SWITCH().client_id {
+        AccessTokenPrefix::None
+    } else {
+        AccessTokenPrefix::D
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct DeviceCodeEvent {
+    user_code: String,
+    verification_uri: String,
+}
+
+// Custom Callback Handler for Tauri
+struct TauriCallbackHandler {
+    window: Window,
+    /// Catches the authorization-code redirect; only set for the browser flow
+    listener: tokio::sync::Mutex<Option<LoopbackListener>>,
+}
+
+#[async_trait]
+impl AuthPromptCallback for TauriCallbackHandler {
+    async fn call(&self, data: AuthPromptData) -> Result<Option<xal::url::Url>, Box<dyn std::error::Error + 'static>> {
+        println!("DEBUG: XAL Callback received: {:?}", data);
+
+        match data {
+            AuthPromptData::DeviceCode { code, full_verificiation_url, .. } => {
+                let event_data = DeviceCodeEvent {
+                    user_code: code.secret().to_string(),
+                    verification_uri: full_verificiation_url.secret().to_string(),
+                };
+
+                self.window.emit("auth-device-code", &event_data)
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + 'static>)?;
+            },
+            AuthPromptData::RedirectUrl { url, .. } => {
+                println!("DEBUG: RedirectUrl flow - opening URL in browser");
+                let event_data = DeviceCodeEvent {
+                    user_code: "NO_CODE".to_string(),
+                    verification_uri: url.to_string(),
+                };
+
+                self.window.emit("auth-redirect-url", &event_data)
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + 'static>)?;
+
+                if let Some(listener) = self.listener.lock().await.take() {
+                    if let Err(e) = tauri_plugin_opener::open_url(url.as_str(), None::<&str>) {
+                        // The frontend still has the URL from the event and can show it
+                        println!("DEBUG: Failed to open browser: {}", e);
+                    }
+
+                    let redirect = listener.wait_for_redirect()
+                        .await
+                        .map_err(Box::<dyn std::error::Error>::from)?;
+                    println!("DEBUG: Captured authorization redirect");
+                    return Ok(Some(redirect));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+/// How the user signs in to their Microsoft account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MicrosoftFlow {
+    /// Enter a code on microsoft.com/link; works everywhere
+    DeviceCode,
+    /// Sign in in the default browser, redirected back to a localhost listener.
+    /// Needs `PORCOS_MS_CLIENT_ID`; without it the device code flow is used.
+    Browser,
+}
+
+/// Microsoft accounts through XAL (Xbox Live) and the Minecraft services API
+pub struct MicrosoftProvider {
+    /// Needed to show the login prompt; refresh/validate work without it
+    window: Option<Window>,
+    flow: MicrosoftFlow,
+}
+
+impl MicrosoftProvider {
+    pub fn new(window: Option<Window>) -> Self {
+        Self { window, flow: MicrosoftFlow::DeviceCode }
+    }
+
+    pub fn with_flow(window: Option<Window>, flow: MicrosoftFlow) -> Self {
+        Self { window, flow }
+    }
+
+    async fn device_code_login(&self, window: Window) -> Result<(XalAuthenticator, TokenStore), AuthError> {
+        // 1. Initialize Authenticator with Minecraft Bedrock Switch profile
+        let mut authenticator = XalAuthenticator::new(
+            MC_BEDROCK_SWITCH(),
+            CLIENT_NINTENDO(),
+            "RETAIL".into(),
+        );
+
+        // 2. Perform Device Code Authentication Flow (like in official examples)
+        let callback_handler = TauriCallbackHandler { window, listener: tokio::sync::Mutex::new(None) };
+
+        println!("DEBUG: Starting Device Code authentication flow...");
+        let token_store = Flows::ms_device_code_flow(
+            &mut authenticator,
+            callback_handler,
+            tokio::time::sleep  // sleep function for polling
+        ).await.map_err(|e| AuthError {
+            message: format!("XAL Auth Failed: {}", e),
+            code: "XAL_AUTH_ERROR".into()
+        })?;
+
+        Ok((authenticator, token_store))
+    }
+
+    /// Authorization-code flow: the redirect lands on a loopback listener instead of a Microsoft page.
+    /// Uses the app registration from `PORCOS_MS_CLIENT_ID`, which must allow localhost redirects.
+    async fn browser_login(&self, window: Window, client_id: String, listener: LoopbackListener) -> Result<(XalAuthenticator, TokenStore), AuthError> {
+        let redirect_uri = xal::oauth2::RedirectUrl::new(listener.redirect_uri())
+            .map_err(|e| AuthError::new("XAL_AUTH_ERROR", format!("Invalid redirect URI: {}", e)))?;
+        let app_params = XalAppParameters {
+            client_id,
+            title_id: None,
+            auth_scopes: vec![
+                xal::oauth2::Scope::new("XboxLive.signin".into()),
+                xal::oauth2::Scope::new("offline_access".into()),
+            ],
+            redirect_uri: Some(redirect_uri),
+            client_secret: None,
+        };
+
+        let mut authenticator = XalAuthenticator::new(
+            app_params,
+            CLIENT_NINTENDO(),
+            "RETAIL".into(),
+        );
+        let callback_handler = TauriCallbackHandler { window, listener: tokio::sync::Mutex::new(Some(listener)) };
+
+        println!("DEBUG: Starting browser authorization-code flow...");
+        let token_store = Flows::ms_authorization_flow(&mut authenticator, callback_handler, false)
+            .await
+            .map_err(|e| AuthError {
+                message: format!("XAL Auth Failed: {}", e),
+                code: "XAL_AUTH_ERROR".into()
+            })?;
+
+        Ok((authenticator, token_store))
+    }
+}
+
+#[async_trait]
+impl AuthProvider for MicrosoftProvider {
+    async fn login(&self, _request: LoginRequest) -> Result<AuthResult, AuthError> {
+        println!("DEBUG: Microsoft login called (XAL-RS - {:?})", self.flow);
+        let window = self.window.clone()
+            .ok_or(AuthError::new("NO_WINDOW", "Microsoft login needs a window to show the login prompt"))?;
+
+        let (mut authenticator, token_store) = match (self.flow, browser_client_id()) {
+            (MicrosoftFlow::Browser, Some(client_id)) => match LoopbackListener::bind().await {
+                Ok(listener) => self.browser_login(window, client_id, listener).await?,
+                Err(e) => {
+                    println!("DEBUG: {}, falling back to Device Code flow", e);
+                    self.device_code_login(window).await?
+                }
+            },
+            (MicrosoftFlow::Browser, None) => {
+                println!("DEBUG: {} is not set, falling back to Device Code flow", BROWSER_CLIENT_ID_VAR);
+                self.device_code_login(window).await?
+            }
+            (MicrosoftFlow::DeviceCode, _) => self.device_code_login(window).await?,
+        };
+
+        println!("DEBUG: Microsoft Auth successful, proceeding with Xbox Live authorization");
+
+        let (result, token_store) = authorize_minecraft(&mut authenticator, token_store).await?;
+        save_session(&result.uuid, &token_store)?;
+
+        println!("DEBUG: Login Complete! User: {}", result.username);
+        Ok(result)
+    }
+
+    /// Silently renew a stored session.
+    /// Refreshes the live token and requests a new Xbox/XSTS/Minecraft token chain, so the
+    /// device code flow only has to run again when the refresh token itself is revoked.
+    async fn refresh(&self, session: &AuthResult) -> Result<AuthResult, AuthError> {
+        println!("DEBUG: Refreshing Microsoft session for {}", session.uuid);
+
+        let mut token_store = load_session(&session.uuid)?;
+
+        let mut authenticator = Flows::try_refresh_live_tokens_from_tokenstore(&mut token_store)
+            .await
+            .map_err(|e| AuthError {
+                message: format!("Live token refresh failed: {}", e),
+                code: "REFRESH_FAILED".into()
+            })?;
+
+        let (result, token_store) = authorize_minecraft(&mut authenticator, token_store).await?;
+        save_session(&result.uuid, &token_store)?;
+
+        println!("DEBUG: Session refreshed for {}", result.username);
+        Ok(result)
+    }
+
+    async fn validate(&self, session: &AuthResult) -> Result<bool, AuthError> {
+        match fetch_profile(&session.access_token).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.code == "PROFILE_UNAUTHORIZED" => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn profile(&self, session: &AuthResult) -> Result<GameProfile, AuthError> {
+        fetch_profile(&session.access_token).await
+    }
+}
+
+async fn fetch_profile(access_token: &str) -> Result<GameProfile, AuthError> {
+    let profile = MinecraftServicesClient::new(None, access_token).get_profile().await?;
+    Ok(GameProfile { id: profile.id, name: profile.name })
+}
+
+/// Exchange the live token held in `token_store` for a Minecraft access token.
+/// Returns the login result together with the updated token store that should be persisted.
+async fn authorize_minecraft(authenticator: &mut XalAuthenticator, token_store: TokenStore) -> Result<(AuthResult, TokenStore), AuthError> {
+    // 3. Continue with traditional Xbox Live authorization (required after Device Code flow)
+    let prefix = token_prefix(&token_store.app_params);
+    let token_store = Flows::xbox_live_authorization_traditional_flow(
+        authenticator,
+        token_store.live_token,
+        xal::Constants::RELYING_PARTY_XBOXLIVE.into(),
+        prefix,
+        false,
+    ).await.map_err(|e| AuthError {
+        message: format!("Xbox Live Auth Failed: {}", e),
+        code: "XBL_AUTH_ERROR".into()
+    })?;
+
+    println!("DEBUG: Xbox Live Auth Successful!");
+
+    // 4. Get XSTS Token for Minecraft (exactly like in the official example)
+    let xsts_mc_services = authenticator
+        .get_xsts_token(
+            token_store.device_token.as_ref(),
+            token_store.title_token.as_ref(),
+            token_store.user_token.as_ref(),
+            "rp://api.minecraftservices.com/"
+        )
+        .await
+        .map_err(|e| AuthError {
+            message: format!("XSTS Exchange Failed: {}", e),
+            code: "XSTS_ERROR".into()
+        })?;
+
+    let identity_token = xsts_mc_services.authorization_header_value();
+    let xuid = xsts_mc_services.userhash();
+    println!("DEBUG: Got Identity Token for Minecraft. XUID: {}", xuid);
+
+    // 5. Login with Xbox to Minecraft Services (exactly like in the official example)
+    let client = reqwest::Client::new();
+    let mc_resp = client.post(format!("{}/authentication/login_with_xbox", MINECRAFT_SERVICES_URL))
+        .json(&serde_json::json!({"identityToken": identity_token}))
+        .send()
+        .await
+        .map_err(|e| AuthError { message: e.to_string(), code: "MC_NET_ERROR".into() })?;
+
+    if !mc_resp.status().is_success() {
+        let text = mc_resp.text().await.unwrap_or_default();
+        return Err(AuthError { message: format!("MC Login Failed: {}", text), code: "MC_AUTH_FAIL".into() });
+    }
+
+    let mc_token_data: serde_json::Value = mc_resp.json().await
+        .map_err(|e| AuthError { message: e.to_string(), code: "MC_PARSE_ERROR".into() })?;
+
+    let mc_access_token = mc_token_data["access_token"].as_str()
+        .ok_or(AuthError { message: "No MC Access Token".into(), code: "MC_TOKEN_MISSING".into() })?;
+    // Minecraft tokens are valid for 24h; trust the server value when present
+    let expires_in = mc_token_data["expires_in"].as_u64().unwrap_or(86400);
+
+    // 6. Check game ownership, then get the Minecraft Profile
+    // A failed entitlement request says nothing about ownership; the profile lookup decides then
+    let services = MinecraftServicesClient::new(None, mc_access_token);
+    let entitlements = match services.get_entitlements().await {
+        Ok(entitlements) => {
+            println!("DEBUG: Entitlements: owns Java = {}, Game Pass = {}", entitlements.owns_java(), entitlements.has_game_pass());
+            Ok(entitlements.owns_java() || entitlements.has_game_pass())
+        }
+        Err(e) => {
+            println!("DEBUG: Entitlement check failed ({}), relying on the profile", e.message);
+            Err(e)
+        }
+    };
+
+    let profile = match services.get_profile().await {
+        Ok(profile) => {
+            if entitlements.as_ref().is_ok_and(|owns_game| !owns_game) {
+                // Entitlements can lag behind for freshly redeemed codes; a profile is proof enough
+                println!("DEBUG: No entitlement listed but a profile exists, continuing");
+            }
+            GameProfile { id: profile.id, name: profile.name }
+        }
+        Err(e) if e.code == "PROFILE_NOT_FOUND" => {
+            return Err(match entitlements {
+                Ok(true) => AuthError::profile_not_created(),
+                Ok(false) => AuthError::game_not_owned(),
+                // Can't tell "not owned" from "no profile yet" without the entitlements
+                Err(e) => e,
+            });
+        }
+        Err(e) => return Err(e),
+    };
+
+    let result = AuthResult {
+        access_token: mc_access_token.to_string(),
+        // The live refresh token stays in the persisted XAL token store (see `refresh`)
+        refresh_token: "managed_by_xal".to_string(),
+        expires_in,
+        username: profile.name,
+        uuid: profile.id,
+        xuid,
+        mode: "microsoft".to_string(),
+        auth_server: None,
+    };
+
+    Ok((result, token_store))
+}
+
+/// Legacy plain-text session files, migrated into the vault on first load
+fn get_sessions_dir() -> PathBuf {
+    let path = MinecraftLauncher::get_launcher_data_dir().join("sessions");
+    if !path.exists() {
+        let _ = fs::create_dir_all(&path);
+    }
+    path
+}
+
+fn session_secret(uuid: &str) -> String {
+    format!("session:{}", uuid)
+}
+
+/// Persist the XAL token store (live/device/title/user tokens) for an account in the credential vault
+fn save_session(uuid: &str, token_store: &TokenStore) -> Result<(), AuthError> {
+    let json = serde_json::to_string(token_store)
+        .map_err(|e| AuthError { message: format!("Failed to serialize session: {}", e), code: "SESSION_WRITE_ERROR".into() })?;
+
+    vault::set_secret(&session_secret(uuid), &json)
+        .map_err(|e| AuthError { message: format!("Failed to write session: {}", e), code: "SESSION_WRITE_ERROR".into() })
+}
+
+fn load_session(uuid: &str) -> Result<TokenStore, AuthError> {
+    let stored = vault::get_secret(&session_secret(uuid))
+        .map_err(|e| AuthError { message: format!("Failed to read session: {}", e), code: "VAULT_LOCKED".into() })?;
+
+    let content = match stored {
+        Some(content) => content,
+        None => {
+            let session_path = get_sessions_dir().join(format!("{}.json", uuid));
+            if !session_path.exists() {
+                return Err(AuthError { message: format!("No stored session for {}", uuid), code: "SESSION_NOT_FOUND".into() });
+            }
+            fs::read_to_string(&session_path)
+                .map_err(|e| AuthError { message: format!("Failed to read session: {}", e), code: "SESSION_READ_ERROR".into() })?
+        }
+    };
+
+    let token_store: TokenStore = serde_json::from_str(&content)
+        .map_err(|e| AuthError { message: format!("Failed to parse session: {}", e), code: "SESSION_READ_ERROR".into() })?;
+
+    // Move sessions from before the vault existed out of plain text
+    let session_path = get_sessions_dir().join(format!("{}.json", uuid));
+    if session_path.exists() && save_session(uuid, &token_store).is_ok() {
+        let _ = fs::remove_file(session_path);
+    }
+    Ok(token_store)
+}
+
+pub(crate) fn delete_session(uuid: &str) {
+    let _ = vault::remove_secret(&session_secret(uuid));
+    let session_path = get_sessions_dir().join(format!("{}.json", uuid));
+    if session_path.exists() {
+        let _ = fs::remove_file(session_path);
+    }
+}
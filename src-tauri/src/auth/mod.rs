@@ -0,0 +1,103 @@
+pub mod loopback;
+pub mod microsoft;
+pub mod offline;
+pub mod services;
+#[cfg(test)]
+pub(crate) mod test_server;
+pub mod token;
+pub mod vault;
+pub mod yggdrasil;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tauri::Window;
+
+pub use microsoft::{MicrosoftFlow, MicrosoftProvider};
+pub use offline::OfflineProvider;
+pub use yggdrasil::YggdrasilProvider;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthResult {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: u64,
+    pub username: String,
+    pub uuid: String,
+    pub xuid: String,
+    pub mode: String,
+    /// API root of the Yggdrasil server for third-party accounts
+    #[serde(default)]
+    pub auth_server: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthError {
+    pub message: String,
+    pub code: String,
+}
+
+/// The Microsoft account has no Java Edition entitlement
+pub const GAME_NOT_OWNED: &str = "GAME_NOT_OWNED";
+/// The account owns the game (usually through Game Pass) but never created a Java profile
+pub const PROFILE_NOT_CREATED: &str = "PROFILE_NOT_CREATED";
+
+impl AuthError {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        Self { message: message.into(), code: code.into() }
+    }
+
+    pub fn game_not_owned() -> Self {
+        Self::new(GAME_NOT_OWNED, "This Microsoft account does not own Minecraft: Java Edition")
+    }
+
+    pub fn profile_not_created() -> Self {
+        Self::new(PROFILE_NOT_CREATED, "This account owns Minecraft but has no Java profile yet. Open minecraft.net or the official launcher once to create one")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameProfile {
+    pub id: String,
+    pub name: String,
+}
+
+/// Credentials for providers that need them (offline username, Yggdrasil username/password)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoginRequest {
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// A source of Minecraft accounts.
+/// The `AuthResult` returned by `login` is the session handed back to `refresh`, `validate` and `profile`.
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    async fn login(&self, request: LoginRequest) -> Result<AuthResult, AuthError>;
+
+    async fn refresh(&self, session: &AuthResult) -> Result<AuthResult, AuthError>;
+
+    /// Whether the session's access token is still accepted by the server
+    async fn validate(&self, session: &AuthResult) -> Result<bool, AuthError>;
+
+    async fn profile(&self, session: &AuthResult) -> Result<GameProfile, AuthError>;
+
+    /// Revoke the session server-side, where the provider supports it
+    async fn invalidate(&self, _session: &AuthResult) -> Result<(), AuthError> {
+        Ok(())
+    }
+}
+
+/// Build the provider for an account `mode` ("microsoft", "offline" or "yggdrasil").
+/// "microsoft_browser" logs in through the browser instead of a device code.
+pub fn provider_for(mode: &str, auth_server: Option<&str>, window: Option<Window>) -> Result<Box<dyn AuthProvider>, AuthError> {
+    match mode {
+        "microsoft" => Ok(Box::new(MicrosoftProvider::new(window))),
+        "microsoft_browser" => Ok(Box::new(MicrosoftProvider::with_flow(window, MicrosoftFlow::Browser))),
+        "offline" => Ok(Box::new(OfflineProvider)),
+        "yggdrasil" => {
+            let server = auth_server.ok_or(AuthError::new("AUTH_SERVER_MISSING", "Yggdrasil accounts need an auth server URL"))?;
+            Ok(Box::new(YggdrasilProvider::new(server)))
+        }
+        _ => Err(AuthError::new("INVALID_MODE", format!("Unknown account mode: {}", mode))),
+    }
+}
@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+use crate::auth::{AuthError, AuthProvider, AuthResult, GameProfile, LoginRequest};
+use crate::launcher::MinecraftLauncher;
+
+/// Offline accounts: no server, the UUID is derived from the username
+pub struct OfflineProvider;
+
+#[async_trait]
+impl AuthProvider for OfflineProvider {
+    async fn login(&self, request: LoginRequest) -> Result<AuthResult, AuthError> {
+        let username = request.username
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty())
+            .ok_or(AuthError::new("USERNAME_MISSING", "Offline accounts need a username"))?;
+
+        println!("Initiating Offline Login for: {}", username);
+        let uuid = MinecraftLauncher::generate_offline_uuid(&username);
+        Ok(AuthResult {
+            access_token: "offline_token".to_string(),
+            refresh_token: "none".to_string(),
+            expires_in: 0,
+            username,
+            uuid,
+            xuid: "0".to_string(),
+            mode: "offline".to_string(),
+            auth_server: None,
+        })
+    }
+
+    async fn refresh(&self, session: &AuthResult) -> Result<AuthResult, AuthError> {
+        Ok(session.clone())
+    }
+
+    async fn validate(&self, _session: &AuthResult) -> Result<bool, AuthError> {
+        Ok(true)
+    }
+
+    async fn profile(&self, session: &AuthResult) -> Result<GameProfile, AuthError> {
+        Ok(GameProfile { id: session.uuid.clone(), name: session.username.clone() })
+    }
+}
@@ -0,0 +1,342 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::auth::AuthError;
+
+pub const MINECRAFT_SERVICES_URL: &str = "https://api.minecraftservices.com";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSkin {
+    pub id: String,
+    pub state: String,
+    pub url: String,
+    #[serde(default)]
+    pub variant: Option<String>,
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileCape {
+    pub id: String,
+    pub state: String,
+    pub url: String,
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MinecraftProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub skins: Vec<ProfileSkin>,
+    #[serde(default)]
+    pub capes: Vec<ProfileCape>,
+}
+
+/// Arm model of a skin
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SkinVariant {
+    Classic,
+    Slim,
+}
+
+impl SkinVariant {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Classic => "classic",
+            Self::Slim => "slim",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntitlementItem {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Entitlements {
+    #[serde(default)]
+    pub items: Vec<EntitlementItem>,
+}
+
+impl Entitlements {
+    /// Java Edition bought outright
+    pub fn owns_java(&self) -> bool {
+        self.items.iter().any(|i| i.name == "game_minecraft" || i.name == "product_minecraft")
+    }
+
+    /// PC Game Pass / Game Pass Ultimate, which includes Java Edition
+    pub fn has_game_pass(&self) -> bool {
+        self.items.iter().any(|i| i.name.starts_with("product_game_pass"))
+    }
+}
+
+/// Quick connectivity probe, used to tell "offline" apart from a rejected refresh
+pub async fn services_reachable() -> bool {
+    let client = match reqwest::Client::builder().timeout(std::time::Duration::from_secs(5)).build() {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+    client.head(MINECRAFT_SERVICES_URL).send().await.is_ok()
+}
+
+/// Client for the profile endpoints of the Minecraft services API.
+/// The base URL is configurable so a local stand-in server can be used.
+pub struct MinecraftServicesClient {
+    base_url: String,
+    access_token: String,
+    client: reqwest::Client,
+}
+
+impl MinecraftServicesClient {
+    pub fn new(base_url: Option<&str>, access_token: &str) -> Self {
+        Self {
+            base_url: base_url.unwrap_or(MINECRAFT_SERVICES_URL).trim_end_matches('/').to_string(),
+            access_token: access_token.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn get_profile(&self) -> Result<MinecraftProfile, AuthError> {
+        let response = self.client.get(format!("{}/minecraft/profile", self.base_url))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .map_err(|e| AuthError::new("PROFILE_NET_ERROR", e.to_string()))?;
+        Self::parse_profile(response).await
+    }
+
+    pub async fn get_entitlements(&self) -> Result<Entitlements, AuthError> {
+        let url = format!("{}/entitlements/license?requestId={}", self.base_url, uuid::Uuid::new_v4());
+        let response = self.client.get(url)
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .map_err(|e| AuthError::new("ENTITLEMENTS_NET_ERROR", e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            return Err(AuthError::new("ENTITLEMENTS_FAIL", format!("Entitlement check returned {}: {}", status, text)));
+        }
+
+        response.json()
+            .await
+            .map_err(|e| AuthError::new("ENTITLEMENTS_PARSE_ERROR", e.to_string()))
+    }
+
+    /// Upload a PNG skin
+    pub async fn upload_skin(&self, png_path: &Path, variant: SkinVariant) -> Result<MinecraftProfile, AuthError> {
+        let bytes = std::fs::read(png_path)
+            .map_err(|e| AuthError::new("SKIN_READ_ERROR", format!("Failed to read skin: {}", e)))?;
+        let file_part = reqwest::multipart::Part::bytes(bytes)
+            .file_name("skin.png")
+            .mime_str("image/png")
+            .map_err(|e| AuthError::new("SKIN_READ_ERROR", e.to_string()))?;
+        let form = reqwest::multipart::Form::new()
+            .text("variant", variant.as_str())
+            .part("file", file_part);
+
+        let response = self.client.post(format!("{}/minecraft/profile/skins", self.base_url))
+            .bearer_auth(&self.access_token)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| AuthError::new("PROFILE_NET_ERROR", e.to_string()))?;
+        self.profile_after(response).await
+    }
+
+    pub async fn reset_skin(&self) -> Result<MinecraftProfile, AuthError> {
+        let response = self.client.delete(format!("{}/minecraft/profile/skins/active", self.base_url))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .map_err(|e| AuthError::new("PROFILE_NET_ERROR", e.to_string()))?;
+        self.profile_after(response).await
+    }
+
+    pub async fn show_cape(&self, cape_id: &str) -> Result<MinecraftProfile, AuthError> {
+        let response = self.client.put(format!("{}/minecraft/profile/capes/active", self.base_url))
+            .bearer_auth(&self.access_token)
+            .json(&serde_json::json!({ "capeId": cape_id }))
+            .send()
+            .await
+            .map_err(|e| AuthError::new("PROFILE_NET_ERROR", e.to_string()))?;
+        self.profile_after(response).await
+    }
+
+    pub async fn hide_cape(&self) -> Result<MinecraftProfile, AuthError> {
+        let response = self.client.delete(format!("{}/minecraft/profile/capes/active", self.base_url))
+            .bearer_auth(&self.access_token)
+            .send()
+            .await
+            .map_err(|e| AuthError::new("PROFILE_NET_ERROR", e.to_string()))?;
+        self.profile_after(response).await
+    }
+
+    /// Mutating endpoints usually answer with the updated profile; fall back to fetching it
+    async fn profile_after(&self, response: reqwest::Response) -> Result<MinecraftProfile, AuthError> {
+        let status = response.status();
+        if !status.is_success() {
+            return Err(Self::status_error(response).await);
+        }
+
+        let text = response.text().await.unwrap_or_default();
+        match serde_json::from_str::<MinecraftProfile>(&text) {
+            Ok(profile) => Ok(profile),
+            Err(_) => self.get_profile().await,
+        }
+    }
+
+    async fn parse_profile(response: reqwest::Response) -> Result<MinecraftProfile, AuthError> {
+        if !response.status().is_success() {
+            return Err(Self::status_error(response).await);
+        }
+
+        response.json()
+            .await
+            .map_err(|e| AuthError::new("PROFILE_PARSE_ERROR", e.to_string()))
+    }
+
+    async fn status_error(response: reqwest::Response) -> AuthError {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        let code = match status {
+            reqwest::StatusCode::UNAUTHORIZED => "PROFILE_UNAUTHORIZED",
+            reqwest::StatusCode::NOT_FOUND => "PROFILE_NOT_FOUND",
+            reqwest::StatusCode::BAD_REQUEST => "PROFILE_BAD_REQUEST",
+            _ => "PROFILE_FAIL",
+        };
+        AuthError::new(code, format!("Minecraft services returned {}: {}", status, text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::test_server::{Response, TestServer};
+
+    fn profile(skin_variant: &str, cape_state: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": "0123456789abcdef0123456789abcdef",
+            "name": "Steve",
+            "skins": [{ "id": "skin-1", "state": "ACTIVE", "url": "http://textures/skin", "variant": skin_variant }],
+            "capes": [{ "id": "cape-1", "state": cape_state, "url": "http://textures/cape", "alias": "Migrator" }],
+        })
+    }
+
+    fn temp_png() -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("porcos-skin-{}.png", uuid::Uuid::new_v4()));
+        std::fs::write(&path, b"\x89PNG\r\n\x1a\nskin").unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn upload_skin_sends_the_png_and_variant() {
+        let server = TestServer::start(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/minecraft/profile/skins") => Response::json(200, profile("SLIM", "INACTIVE")),
+            _ => Response::empty(404),
+        }).await;
+        let png = temp_png();
+
+        let result = MinecraftServicesClient::new(Some(&server.url), "mc-token").upload_skin(&png, SkinVariant::Slim).await;
+        let _ = std::fs::remove_file(&png);
+
+        let request = server.request_to("POST", "/minecraft/profile/skins");
+        assert_eq!(request.header("authorization"), Some("Bearer mc-token"));
+        assert!(request.header("content-type").is_some_and(|value| value.starts_with("multipart/form-data")));
+        let body = request.body_text();
+        assert!(body.contains("name=\"variant\"\r\n\r\nslim\r\n"));
+        assert!(body.contains("filename=\"skin.png\""));
+        assert!(body.contains("PNG"));
+        assert_eq!(result.unwrap().skins[0].variant.as_deref(), Some("SLIM"));
+    }
+
+    #[tokio::test]
+    async fn upload_skin_fetches_the_profile_when_the_answer_has_none() {
+        let server = TestServer::start(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/minecraft/profile/skins") => Response::empty(200),
+            ("GET", "/minecraft/profile") => Response::json(200, profile("CLASSIC", "INACTIVE")),
+            _ => Response::empty(404),
+        }).await;
+        let png = temp_png();
+
+        let result = MinecraftServicesClient::new(Some(&server.url), "mc-token").upload_skin(&png, SkinVariant::Classic).await;
+        let _ = std::fs::remove_file(&png);
+
+        assert_eq!(result.unwrap().name, "Steve");
+        assert!(server.request_to("POST", "/minecraft/profile/skins").body_text().contains("classic"));
+        server.request_to("GET", "/minecraft/profile");
+    }
+
+    #[tokio::test]
+    async fn upload_skin_reports_a_missing_file() {
+        let client = MinecraftServicesClient::new(Some("http://127.0.0.1:9"), "mc-token");
+        let error = client.upload_skin(Path::new("/nonexistent/skin.png"), SkinVariant::Classic).await.unwrap_err();
+        assert_eq!(error.code, "SKIN_READ_ERROR");
+    }
+
+    #[tokio::test]
+    async fn reset_skin_deletes_the_active_skin() {
+        let server = TestServer::start(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("DELETE", "/minecraft/profile/skins/active") => Response::json(200, profile("CLASSIC", "INACTIVE")),
+            _ => Response::empty(404),
+        }).await;
+
+        let result = MinecraftServicesClient::new(Some(&server.url), "mc-token").reset_skin().await.unwrap();
+
+        assert_eq!(server.request_to("DELETE", "/minecraft/profile/skins/active").header("authorization"), Some("Bearer mc-token"));
+        assert_eq!(result.skins.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn show_cape_selects_the_cape() {
+        let server = TestServer::start(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("PUT", "/minecraft/profile/capes/active") => Response::json(200, profile("CLASSIC", "ACTIVE")),
+            _ => Response::empty(404),
+        }).await;
+
+        let result = MinecraftServicesClient::new(Some(&server.url), "mc-token").show_cape("cape-1").await.unwrap();
+
+        let body = server.request_to("PUT", "/minecraft/profile/capes/active").json();
+        assert_eq!(body["capeId"], "cape-1");
+        assert_eq!(result.capes[0].state, "ACTIVE");
+    }
+
+    #[tokio::test]
+    async fn hide_cape_deletes_the_active_cape() {
+        let server = TestServer::start(|request| match (request.method.as_str(), request.path.as_str()) {
+            ("DELETE", "/minecraft/profile/capes/active") => Response::json(200, profile("CLASSIC", "INACTIVE")),
+            _ => Response::empty(404),
+        }).await;
+
+        let result = MinecraftServicesClient::new(Some(&server.url), "mc-token").hide_cape().await.unwrap();
+
+        server.request_to("DELETE", "/minecraft/profile/capes/active");
+        assert_eq!(result.capes[0].state, "INACTIVE");
+    }
+
+    #[tokio::test]
+    async fn error_statuses_map_to_codes() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/minecraft/profile/capes/active" => Response::json(400, serde_json::json!({ "errorMessage": "unknown cape" })),
+            "/minecraft/profile/skins/active" => Response::empty(401),
+            _ => Response::empty(404),
+        }).await;
+        let client = MinecraftServicesClient::new(Some(&server.url), "expired");
+
+        assert_eq!(client.show_cape("nope").await.unwrap_err().code, "PROFILE_BAD_REQUEST");
+        assert_eq!(client.reset_skin().await.unwrap_err().code, "PROFILE_UNAUTHORIZED");
+        assert_eq!(client.get_profile().await.unwrap_err().code, "PROFILE_NOT_FOUND");
+    }
+
+    #[test]
+    fn skin_variant_only_accepts_known_models() {
+        assert_eq!(serde_json::from_str::<SkinVariant>("\"slim\"").unwrap(), SkinVariant::Slim);
+        assert_eq!(serde_json::from_str::<SkinVariant>("\"classic\"").unwrap(), SkinVariant::Classic);
+        assert!(serde_json::from_str::<SkinVariant>("\"wide\"").is_err());
+    }
+}
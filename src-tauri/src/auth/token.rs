@@ -0,0 +1,27 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+/// Tokens this close to expiry are treated as expired, so they don't run out mid-launch
+pub const EXPIRY_LEEWAY_SECS: u64 = 60;
+
+/// Read the `exp` claim (seconds since the UNIX epoch) from a JWT without verifying it.
+/// Returns None for tokens that aren't JWTs, like offline or most Yggdrasil tokens.
+pub fn jwt_expiry(token: &str) -> Option<u64> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+    let claims: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    claims["exp"].as_u64()
+}
+
+/// Whether the token is known to be expired (or about to be). Unknown expiry counts as valid.
+pub fn is_expired(token: &str) -> bool {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    match jwt_expiry(token) {
+        Some(exp) => exp <= now + EXPIRY_LEEWAY_SECS,
+        None => false,
+    }
+}
@@ -0,0 +1,360 @@
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use zeroize::Zeroizing;
+use crate::launcher::MinecraftLauncher;
+use crate::error::{LauncherError, LauncherResult};
+
+/// Current on-disk format. Bump it and add a migration in `read_vault_file` when the layout changes.
+pub const VAULT_VERSION: u32 = 1;
+
+const KEYRING_SERVICE: &str = "PorcosLauncher";
+const KEYRING_USER: &str = "credential-vault";
+
+/// Wiped from memory when dropped, i.e. on `lock`, `rotate` and `wipe`
+type VaultKey = Zeroizing<[u8; 32]>;
+
+/// `LauncherError::Vault` reasons the UI acts on
+pub const KEYRING_UNAVAILABLE: &str = "KEYRING_UNAVAILABLE";
+pub const VAULT_LOCKED: &str = "VAULT_LOCKED";
+pub const PASSPHRASE_NOT_USED: &str = "PASSPHRASE_NOT_USED";
+
+/// Where the vault key comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeySource {
+    /// Random key stored in the OS keyring (Keychain, Credential Manager, Secret Service)
+    Keyring,
+    /// Key derived from a user passphrase with Argon2id
+    Passphrase,
+}
+
+/// Encrypted vault as stored in `.porcos/vault.json`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VaultFile {
+    version: u32,
+    key_source: KeySource,
+    /// Argon2id salt, base64; only for passphrase vaults
+    #[serde(default)]
+    salt: Option<String>,
+    /// XChaCha20-Poly1305 nonce, base64
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VaultContents {
+    secrets: BTreeMap<String, String>,
+}
+
+struct UnlockedVault {
+    key: VaultKey,
+    key_source: KeySource,
+    salt: Option<Vec<u8>>,
+    contents: VaultContents,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    pub initialized: bool,
+    pub unlocked: bool,
+    pub key_source: Option<KeySource>,
+    pub version: u32,
+    /// Secrets held in memory until the vault is unlocked
+    pub pending: usize,
+}
+
+/// The decrypted vault lives here while unlocked; locking drops the key and the secrets
+static UNLOCKED: Mutex<Option<UnlockedVault>> = Mutex::new(None);
+
+/// Secrets written while the vault could not be opened (no keyring, or a locked passphrase vault).
+/// They keep the session usable and are saved by the next successful `unlock`.
+static PENDING: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+
+fn get_vault_path() -> PathBuf {
+    let dir = MinecraftLauncher::get_launcher_data_dir();
+    if !dir.exists() {
+        let _ = fs::create_dir_all(&dir);
+    }
+    dir.join("vault.json")
+}
+
+pub fn status() -> VaultStatus {
+    let file = read_vault_file().ok().flatten();
+    VaultStatus {
+        initialized: file.is_some(),
+        unlocked: UNLOCKED.lock().unwrap().is_some(),
+        key_source: file.map(|f| f.key_source),
+        version: VAULT_VERSION,
+        pending: PENDING.lock().unwrap().len(),
+    }
+}
+
+/// Unlock the vault, creating it on first use.
+/// Without a passphrase the key comes from the OS keyring.
+/// Pending secrets are saved into the vault once it is open.
+pub fn unlock(passphrase: Option<&str>) -> LauncherResult<()> {
+    let mut guard = UNLOCKED.lock().unwrap();
+
+    let file = match read_vault_file()? {
+        Some(file) => file,
+        None => {
+            let mut vault = new_vault(passphrase, VaultContents::default())?;
+            if vault.key_source == KeySource::Keyring {
+                store_keyring_key(&vault.key)?;
+            }
+            vault.contents.secrets.append(&mut PENDING.lock().unwrap());
+            write_vault(&vault)?;
+            println!("[Vault] Created credential vault ({:?})", vault.key_source);
+            *guard = Some(vault);
+            return Ok(());
+        }
+    };
+
+    let salt = file.salt.as_deref()
+        .map(|s| BASE64.decode(s).map_err(|e| LauncherError::vault(format!("Corrupt vault salt: {}", e))))
+        .transpose()?;
+    let key = match file.key_source {
+        KeySource::Keyring => {
+            if passphrase.is_some() {
+                return Err(LauncherError::vault_reason(PASSPHRASE_NOT_USED, "This vault is protected by the OS keyring, not a passphrase"));
+            }
+            read_keyring_key()?
+        }
+        KeySource::Passphrase => {
+            let passphrase = passphrase.ok_or_else(|| LauncherError::vault_reason(VAULT_LOCKED, "This vault is protected by a passphrase"))?;
+            derive_key(passphrase, salt.as_deref().ok_or_else(|| LauncherError::vault("Corrupt vault: missing salt"))?)?
+        }
+    };
+
+    let nonce = BASE64.decode(&file.nonce).map_err(|e| LauncherError::vault(format!("Corrupt vault nonce: {}", e)))?;
+    let ciphertext = BASE64.decode(&file.ciphertext).map_err(|e| LauncherError::vault(format!("Corrupt vault data: {}", e)))?;
+    let plaintext = XChaCha20Poly1305::new(Key::from_slice(key.as_ref()))
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| LauncherError::vault("Failed to unlock vault: wrong passphrase or key"))?;
+    let contents: VaultContents = serde_json::from_slice(&plaintext)
+        .map_err(|e| LauncherError::parse(format!("Failed to parse vault contents: {}", e)))?;
+
+    println!("[Vault] Unlocked ({} secrets)", contents.secrets.len());
+    let mut vault = UnlockedVault { key, key_source: file.key_source, salt, contents };
+    let mut pending = PENDING.lock().unwrap();
+    if !pending.is_empty() {
+        println!("[Vault] Saving {} pending secrets", pending.len());
+        vault.contents.secrets.extend(pending.iter().map(|(name, value)| (name.clone(), value.clone())));
+        write_vault(&vault)?;
+        pending.clear();
+    }
+    *guard = Some(vault);
+    Ok(())
+}
+
+pub fn lock() {
+    *UNLOCKED.lock().unwrap() = None;
+    println!("[Vault] Locked");
+}
+
+/// Re-encrypt the vault under a fresh key: a new passphrase, or a new keyring key when `None`
+pub fn rotate(new_passphrase: Option<&str>) -> LauncherResult<()> {
+    ensure_unlocked()?;
+    let mut guard = UNLOCKED.lock().unwrap();
+    let current = guard.as_ref().ok_or_else(|| LauncherError::vault("Credential vault is locked"))?;
+
+    let contents = VaultContents { secrets: current.contents.secrets.clone() };
+    let rotated = new_vault(new_passphrase, contents)?;
+    write_vault(&rotated)?;
+    // The keyring still holds the old key here, so a failed store rolls the vault back to it
+    if rotated.key_source == KeySource::Keyring {
+        if let Err(e) = store_keyring_key(&rotated.key) {
+            write_vault(current)?;
+            return Err(e);
+        }
+    } else if current.key_source == KeySource::Keyring {
+        delete_keyring_key();
+    }
+
+    println!("[Vault] Key rotated ({:?})", rotated.key_source);
+    *guard = Some(rotated);
+    Ok(())
+}
+
+/// Delete the vault, its keyring entry and every secret in it
+pub fn wipe() -> LauncherResult<()> {
+    *UNLOCKED.lock().unwrap() = None;
+    PENDING.lock().unwrap().clear();
+    let path = get_vault_path();
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| LauncherError::io(format!("Failed to delete vault: {}", e)))?;
+    }
+    delete_keyring_key();
+    println!("[Vault] Wiped");
+    Ok(())
+}
+
+pub fn get_secret(name: &str) -> LauncherResult<Option<String>> {
+    if let Some(value) = PENDING.lock().unwrap().get(name) {
+        return Ok(Some(value.clone()));
+    }
+    ensure_unlocked()?;
+    let guard = UNLOCKED.lock().unwrap();
+    let vault = guard.as_ref().ok_or_else(|| LauncherError::vault("Credential vault is locked"))?;
+    Ok(vault.contents.secrets.get(name).cloned())
+}
+
+/// Store a secret. When the vault cannot be opened it is kept in memory as pending
+/// and the caller carries on; `status().pending` tells the UI to ask for a passphrase.
+pub fn set_secret(name: &str, value: &str) -> LauncherResult<()> {
+    if let Err(e) = ensure_unlocked() {
+        if !is_recoverable(&e) {
+            return Err(e);
+        }
+        println!("[Vault] Keeping '{}' in memory until the vault is unlocked: {}", name, e);
+        PENDING.lock().unwrap().insert(name.to_string(), value.to_string());
+        return Ok(());
+    }
+    let mut guard = UNLOCKED.lock().unwrap();
+    let vault = guard.as_mut().ok_or_else(|| LauncherError::vault("Credential vault is locked"))?;
+    if vault.contents.secrets.get(name).map(String::as_str) == Some(value) {
+        return Ok(());
+    }
+    vault.contents.secrets.insert(name.to_string(), value.to_string());
+    write_vault(vault)
+}
+
+pub fn remove_secret(name: &str) -> LauncherResult<()> {
+    let was_pending = PENDING.lock().unwrap().remove(name).is_some();
+    if was_pending && UNLOCKED.lock().unwrap().is_none() {
+        return Ok(());
+    }
+    ensure_unlocked()?;
+    let mut guard = UNLOCKED.lock().unwrap();
+    let vault = guard.as_mut().ok_or_else(|| LauncherError::vault("Credential vault is locked"))?;
+    if vault.contents.secrets.remove(name).is_some() {
+        write_vault(vault)?;
+    }
+    Ok(())
+}
+
+/// Keyring-backed vaults (and a missing vault) unlock on first use; passphrase vaults need `unlock`
+fn ensure_unlocked() -> LauncherResult<()> {
+    if UNLOCKED.lock().unwrap().is_some() {
+        return Ok(());
+    }
+
+    match read_vault_file()? {
+        Some(file) if file.key_source == KeySource::Passphrase => {
+            Err(LauncherError::vault_reason(VAULT_LOCKED, "Credential vault is locked. Unlock it with your passphrase."))
+        }
+        _ => unlock(None),
+    }
+}
+
+/// Errors the user can fix by unlocking or setting a passphrase
+fn is_recoverable(error: &LauncherError) -> bool {
+    matches!(error, LauncherError::Vault { reason: Some(reason), .. } if reason == KEYRING_UNAVAILABLE || reason == VAULT_LOCKED)
+}
+
+fn new_vault(passphrase: Option<&str>, contents: VaultContents) -> LauncherResult<UnlockedVault> {
+    match passphrase {
+        Some(passphrase) => {
+            if passphrase.is_empty() {
+                return Err(LauncherError::vault("Passphrase cannot be empty"));
+            }
+            let mut salt = vec![0u8; 16];
+            rand::thread_rng().fill_bytes(&mut salt);
+            let key = derive_key(passphrase, &salt)?;
+            Ok(UnlockedVault { key, key_source: KeySource::Passphrase, salt: Some(salt), contents })
+        }
+        None => {
+            // Only generated here; callers store it in the keyring once the vault is safely written
+            let mut key = Zeroizing::new([0u8; 32]);
+            rand::thread_rng().fill_bytes(key.as_mut());
+            Ok(UnlockedVault { key, key_source: KeySource::Keyring, salt: None, contents })
+        }
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> LauncherResult<VaultKey> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|e| LauncherError::vault(format!("Failed to derive vault key: {}", e)))?;
+    Ok(key)
+}
+
+fn keyring_entry() -> LauncherResult<keyring::Entry> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| LauncherError::vault_reason(KEYRING_UNAVAILABLE, format!("OS keyring unavailable: {}", e)))
+}
+
+fn read_keyring_key() -> LauncherResult<VaultKey> {
+    let encoded = Zeroizing::new(keyring_entry()?.get_password()
+        .map_err(|e| LauncherError::vault_reason(KEYRING_UNAVAILABLE, format!("Failed to read vault key from the OS keyring: {}", e)))?);
+    let bytes = Zeroizing::new(BASE64.decode(encoded.as_bytes()).map_err(|e| LauncherError::vault(format!("Corrupt keyring entry: {}", e)))?);
+    if bytes.len() != 32 {
+        return Err(LauncherError::vault("Corrupt keyring entry: wrong key length"));
+    }
+    let mut key = Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&bytes);
+    Ok(key)
+}
+
+fn store_keyring_key(key: &VaultKey) -> LauncherResult<()> {
+    keyring_entry()?.set_password(&Zeroizing::new(BASE64.encode(key.as_ref())))
+        .map_err(|e| LauncherError::vault_reason(KEYRING_UNAVAILABLE, format!("Failed to store vault key in the OS keyring: {}", e)))
+}
+
+fn delete_keyring_key() {
+    if let Ok(entry) = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER) {
+        let _ = entry.delete_credential();
+    }
+}
+
+fn read_vault_file() -> LauncherResult<Option<VaultFile>> {
+    let path = get_vault_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| LauncherError::io(format!("Failed to read vault: {}", e)))?;
+    let file: VaultFile = serde_json::from_str(&content).map_err(|e| LauncherError::parse(format!("Failed to parse vault: {}", e)))?;
+    if file.version > VAULT_VERSION {
+        return Err(LauncherError::vault(format!("Vault format v{} is newer than this launcher supports (v{})", file.version, VAULT_VERSION)));
+    }
+    Ok(Some(file))
+}
+
+fn write_vault(vault: &UnlockedVault) -> LauncherResult<()> {
+    let plaintext = serde_json::to_vec(&vault.contents)
+        .map_err(|e| LauncherError::parse(format!("Failed to serialize vault: {}", e)))?;
+
+    let mut nonce = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let ciphertext = XChaCha20Poly1305::new(Key::from_slice(vault.key.as_ref()))
+        .encrypt(XNonce::from_slice(&nonce), plaintext.as_ref())
+        .map_err(|_| LauncherError::vault("Failed to encrypt vault"))?;
+
+    let file = VaultFile {
+        version: VAULT_VERSION,
+        key_source: vault.key_source,
+        salt: vault.salt.as_ref().map(|s| BASE64.encode(s)),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| LauncherError::parse(format!("Failed to serialize vault: {}", e)))?;
+
+    // Write next to the vault and rename, so a crash never leaves a half-written file
+    let path = get_vault_path();
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, json).map_err(|e| LauncherError::io(format!("Failed to write vault: {}", e)))?;
+    fs::rename(&tmp_path, &path).map_err(|e| LauncherError::io(format!("Failed to write vault: {}", e)))
+}
@@ -0,0 +1,412 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+use crate::auth::{AuthError, AuthProvider, AuthResult, GameProfile, LoginRequest};
+
+/// authlib-injector's API Location Indication header
+const API_LOCATION_HEADER: &str = "x-authlib-injector-api-location";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthenticateResponse {
+    access_token: String,
+    client_token: String,
+    #[serde(default)]
+    available_profiles: Vec<GameProfile>,
+    selected_profile: Option<GameProfile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YggdrasilErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+/// Third-party Yggdrasil auth servers (Ely.by, self-hosted, ...) speaking the authserver protocol.
+/// `api_root` is the authlib-injector API root, e.g. `https://authserver.example.com/api/yggdrasil`.
+pub struct YggdrasilProvider {
+    api_root: String,
+    client: reqwest::Client,
+}
+
+impl YggdrasilProvider {
+    pub fn new(api_root: &str) -> Self {
+        Self {
+            api_root: api_root.trim_end_matches('/').to_string(),
+            client: reqwest::Client::builder()
+                .user_agent("PorcosLauncher/1.0")
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Follow the API Location Indication header so users can type the server's homepage URL
+    async fn resolve_api_root(&self) -> String {
+        let response = match self.client.get(&self.api_root).send().await {
+            Ok(response) => response,
+            Err(_) => return self.api_root.clone(),
+        };
+
+        let location = response.headers()
+            .get(API_LOCATION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| response.url().join(value).ok());
+
+        match location {
+            Some(url) => url.to_string().trim_end_matches('/').to_string(),
+            None => self.api_root.clone(),
+        }
+    }
+
+    async fn post(&self, api_root: &str, endpoint: &str, body: serde_json::Value) -> Result<reqwest::Response, AuthError> {
+        let response = self.client.post(format!("{}/authserver/{}", api_root, endpoint))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AuthError::new("YGGDRASIL_NET_ERROR", e.to_string()))?;
+
+        if response.status().is_success() {
+            return Ok(response);
+        }
+
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        match serde_json::from_str::<YggdrasilErrorResponse>(&text) {
+            Ok(error) if error.error == "ForbiddenOperationException" => Err(AuthError::new(
+                "INVALID_CREDENTIALS",
+                error.error_message.unwrap_or(error.error),
+            )),
+            Ok(error) => Err(AuthError::new(
+                "YGGDRASIL_ERROR",
+                format!("{}: {}", error.error, error.error_message.unwrap_or_default()),
+            )),
+            Err(_) => Err(AuthError::new("YGGDRASIL_ERROR", format!("Auth server returned {}: {}", status, text))),
+        }
+    }
+
+    fn to_result(&self, api_root: &str, response: AuthenticateResponse, profile: GameProfile) -> AuthResult {
+        AuthResult {
+            access_token: response.access_token,
+            // Yggdrasil refreshes need the client token the server bound the session to
+            refresh_token: response.client_token,
+            expires_in: 0,
+            username: profile.name,
+            uuid: profile.id,
+            xuid: "0".to_string(),
+            mode: "yggdrasil".to_string(),
+            auth_server: Some(api_root.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl AuthProvider for YggdrasilProvider {
+    async fn login(&self, request: LoginRequest) -> Result<AuthResult, AuthError> {
+        let username = request.username.ok_or(AuthError::new("USERNAME_MISSING", "A username or email is required"))?;
+        let password = request.password.unwrap_or_default();
+        let api_root = self.resolve_api_root().await;
+        println!("[Yggdrasil] Authenticating {} against {}", username, api_root);
+
+        let client_token = uuid::Uuid::new_v4().simple().to_string();
+        let response: AuthenticateResponse = self.post(&api_root, "authenticate", serde_json::json!({
+            "agent": { "name": "Minecraft", "version": 1 },
+            "username": username,
+            "password": password,
+            "clientToken": client_token,
+            "requestUser": true,
+        })).await?
+            .json()
+            .await
+            .map_err(|e| AuthError::new("YGGDRASIL_PARSE_ERROR", e.to_string()))?;
+
+        if let Some(profile) = response.selected_profile.clone() {
+            return Ok(self.to_result(&api_root, response, profile));
+        }
+
+        // No profile bound yet: bind the first available one through a refresh
+        let profile = response.available_profiles.first().cloned()
+            .ok_or(AuthError::new("NO_PROFILE", "This account has no Minecraft profile on the auth server"))?;
+
+        let refreshed: AuthenticateResponse = self.post(&api_root, "refresh", serde_json::json!({
+            "accessToken": response.access_token,
+            "clientToken": response.client_token,
+            "selectedProfile": { "id": profile.id, "name": profile.name },
+            "requestUser": true,
+        })).await?
+            .json()
+            .await
+            .map_err(|e| AuthError::new("YGGDRASIL_PARSE_ERROR", e.to_string()))?;
+
+        let profile = refreshed.selected_profile.clone().unwrap_or(profile);
+        Ok(self.to_result(&api_root, refreshed, profile))
+    }
+
+    async fn refresh(&self, session: &AuthResult) -> Result<AuthResult, AuthError> {
+        let response: AuthenticateResponse = self.post(&self.api_root, "refresh", serde_json::json!({
+            "accessToken": session.access_token,
+            "clientToken": session.refresh_token,
+            "requestUser": true,
+        })).await?
+            .json()
+            .await
+            .map_err(|e| AuthError::new("YGGDRASIL_PARSE_ERROR", e.to_string()))?;
+
+        let profile = response.selected_profile.clone()
+            .unwrap_or(GameProfile { id: session.uuid.clone(), name: session.username.clone() });
+        Ok(self.to_result(&self.api_root, response, profile))
+    }
+
+    async fn validate(&self, session: &AuthResult) -> Result<bool, AuthError> {
+        match self.post(&self.api_root, "validate", serde_json::json!({
+            "accessToken": session.access_token,
+            "clientToken": session.refresh_token,
+        })).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.code == "INVALID_CREDENTIALS" => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn profile(&self, session: &AuthResult) -> Result<GameProfile, AuthError> {
+        let url = format!("{}/sessionserver/session/minecraft/profile/{}", self.api_root, session.uuid.replace('-', ""));
+        let response = self.client.get(&url)
+            .send()
+            .await
+            .map_err(|e| AuthError::new("YGGDRASIL_NET_ERROR", e.to_string()))?;
+
+        if !response.status().is_success() || response.status() == reqwest::StatusCode::NO_CONTENT {
+            return Err(AuthError::new("PROFILE_FAIL", format!("Profile lookup failed with status {}", response.status())));
+        }
+
+        response.json()
+            .await
+            .map_err(|e| AuthError::new("YGGDRASIL_PARSE_ERROR", e.to_string()))
+    }
+
+    async fn invalidate(&self, session: &AuthResult) -> Result<(), AuthError> {
+        self.post(&self.api_root, "invalidate", serde_json::json!({
+            "accessToken": session.access_token,
+            "clientToken": session.refresh_token,
+        })).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::test_server::{Response, TestServer};
+
+    const PROFILE_ID: &str = "0123456789abcdef0123456789abcdef";
+
+    fn session(server: &TestServer) -> AuthResult {
+        AuthResult {
+            access_token: "old-access".into(),
+            refresh_token: "client-token".into(),
+            expires_in: 0,
+            username: "Steve".into(),
+            uuid: PROFILE_ID.into(),
+            xuid: "0".into(),
+            mode: "yggdrasil".into(),
+            auth_server: Some(server.url.clone()),
+        }
+    }
+
+    fn forbidden() -> Response {
+        Response::json(403, serde_json::json!({ "error": "ForbiddenOperationException", "errorMessage": "Invalid credentials." }))
+    }
+
+    fn login(username: &str, password: &str) -> LoginRequest {
+        LoginRequest { username: Some(username.into()), password: Some(password.into()) }
+    }
+
+    #[tokio::test]
+    async fn authenticate_uses_the_selected_profile() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/authserver/authenticate" => Response::json(200, serde_json::json!({
+                "accessToken": "new-access",
+                "clientToken": request.json()["clientToken"],
+                "selectedProfile": { "id": PROFILE_ID, "name": "Steve" },
+            })),
+            _ => Response::empty(404),
+        }).await;
+
+        let result = YggdrasilProvider::new(&server.url).login(login("steve@example.com", "hunter2")).await.unwrap();
+
+        let request = server.request_to("POST", "/authserver/authenticate");
+        assert!(request.header("content-type").is_some_and(|value| value.starts_with("application/json")));
+        let body = request.json();
+        assert_eq!(body["username"], "steve@example.com");
+        assert_eq!(body["password"], "hunter2");
+        assert_eq!(body["agent"]["name"], "Minecraft");
+        assert_eq!(result.access_token, "new-access");
+        assert_eq!(result.refresh_token, body["clientToken"].as_str().unwrap());
+        assert_eq!(result.uuid, PROFILE_ID);
+        assert_eq!(result.username, "Steve");
+        assert_eq!(result.mode, "yggdrasil");
+        assert_eq!(result.auth_server.as_deref(), Some(server.url.as_str()));
+    }
+
+    #[tokio::test]
+    async fn authenticate_binds_the_first_available_profile() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/authserver/authenticate" => Response::json(200, serde_json::json!({
+                "accessToken": "unbound",
+                "clientToken": "client-token",
+                "availableProfiles": [{ "id": PROFILE_ID, "name": "Steve" }, { "id": "ffff", "name": "Alex" }],
+            })),
+            "/authserver/refresh" => Response::json(200, serde_json::json!({
+                "accessToken": "bound",
+                "clientToken": "client-token",
+                "selectedProfile": request.json()["selectedProfile"],
+            })),
+            _ => Response::empty(404),
+        }).await;
+
+        let result = YggdrasilProvider::new(&server.url).login(login("steve", "pw")).await.unwrap();
+
+        let refresh = server.request_to("POST", "/authserver/refresh").json();
+        assert_eq!(refresh["accessToken"], "unbound");
+        assert_eq!(refresh["selectedProfile"]["id"], PROFILE_ID);
+        assert_eq!(result.access_token, "bound");
+        assert_eq!(result.username, "Steve");
+    }
+
+    #[tokio::test]
+    async fn authenticate_rejects_wrong_credentials() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/authserver/authenticate" => forbidden(),
+            _ => Response::empty(404),
+        }).await;
+
+        let error = YggdrasilProvider::new(&server.url).login(login("steve", "wrong")).await.unwrap_err();
+        assert_eq!(error.code, "INVALID_CREDENTIALS");
+        assert_eq!(error.message, "Invalid credentials.");
+    }
+
+    #[tokio::test]
+    async fn authenticate_without_profiles_fails() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/authserver/authenticate" => Response::json(200, serde_json::json!({
+                "accessToken": "a", "clientToken": "c", "availableProfiles": [],
+            })),
+            _ => Response::empty(404),
+        }).await;
+
+        let error = YggdrasilProvider::new(&server.url).login(login("steve", "pw")).await.unwrap_err();
+        assert_eq!(error.code, "NO_PROFILE");
+    }
+
+    #[tokio::test]
+    async fn login_follows_the_api_location_header() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/" => Response::empty(200).header("X-Authlib-Injector-API-Location", "/api/yggdrasil/"),
+            "/api/yggdrasil/authserver/authenticate" => Response::json(200, serde_json::json!({
+                "accessToken": "a", "clientToken": "c",
+                "selectedProfile": { "id": PROFILE_ID, "name": "Steve" },
+            })),
+            _ => Response::empty(404),
+        }).await;
+
+        let result = YggdrasilProvider::new(&format!("{}/", server.url)).login(login("steve", "pw")).await.unwrap();
+
+        server.request_to("POST", "/api/yggdrasil/authserver/authenticate");
+        assert_eq!(result.auth_server, Some(format!("{}/api/yggdrasil", server.url)));
+    }
+
+    #[tokio::test]
+    async fn login_without_api_location_header_keeps_the_url() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/authserver/authenticate" => Response::json(200, serde_json::json!({
+                "accessToken": "a", "clientToken": "c",
+                "selectedProfile": { "id": PROFILE_ID, "name": "Steve" },
+            })),
+            _ => Response::empty(404),
+        }).await;
+
+        let result = YggdrasilProvider::new(&server.url).login(login("steve", "pw")).await.unwrap();
+        assert_eq!(result.auth_server.as_deref(), Some(server.url.as_str()));
+    }
+
+    #[tokio::test]
+    async fn refresh_sends_the_session_tokens() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            "/authserver/refresh" => Response::json(200, serde_json::json!({
+                "accessToken": "refreshed",
+                "clientToken": request.json()["clientToken"],
+                "selectedProfile": { "id": PROFILE_ID, "name": "Steve2" },
+            })),
+            _ => Response::empty(404),
+        }).await;
+
+        let result = YggdrasilProvider::new(&server.url).refresh(&session(&server)).await.unwrap();
+
+        let body = server.request_to("POST", "/authserver/refresh").json();
+        assert_eq!(body["accessToken"], "old-access");
+        assert_eq!(body["clientToken"], "client-token");
+        assert_eq!(result.access_token, "refreshed");
+        assert_eq!(result.refresh_token, "client-token");
+        assert_eq!(result.username, "Steve2");
+    }
+
+    #[tokio::test]
+    async fn refresh_with_a_revoked_token_fails() {
+        let server = TestServer::start(|_| forbidden()).await;
+
+        let error = YggdrasilProvider::new(&server.url).refresh(&session(&server)).await.unwrap_err();
+        assert_eq!(error.code, "INVALID_CREDENTIALS");
+    }
+
+    #[tokio::test]
+    async fn validate_maps_the_server_answer() {
+        let server = TestServer::start(|request| match request.json()["accessToken"].as_str() {
+            Some("old-access") => Response::empty(204),
+            _ => forbidden(),
+        }).await;
+        let provider = YggdrasilProvider::new(&server.url);
+
+        assert!(provider.validate(&session(&server)).await.unwrap());
+        let expired = AuthResult { access_token: "expired".into(), ..session(&server) };
+        assert!(!provider.validate(&expired).await.unwrap());
+
+        let body = server.request_to("POST", "/authserver/validate").json();
+        assert_eq!(body["clientToken"], "client-token");
+    }
+
+    #[tokio::test]
+    async fn validate_reports_server_errors() {
+        let server = TestServer::start(|_| Response::empty(500)).await;
+
+        let error = YggdrasilProvider::new(&server.url).validate(&session(&server)).await.unwrap_err();
+        assert_eq!(error.code, "YGGDRASIL_ERROR");
+    }
+
+    #[tokio::test]
+    async fn invalidate_revokes_the_session() {
+        let server = TestServer::start(|_| Response::empty(204)).await;
+
+        YggdrasilProvider::new(&server.url).invalidate(&session(&server)).await.unwrap();
+
+        let body = server.request_to("POST", "/authserver/invalidate").json();
+        assert_eq!(body["accessToken"], "old-access");
+        assert_eq!(body["clientToken"], "client-token");
+    }
+
+    #[tokio::test]
+    async fn profile_is_read_from_the_session_server() {
+        let server = TestServer::start(|request| match request.path.as_str() {
+            p if p == format!("/sessionserver/session/minecraft/profile/{}", PROFILE_ID) => {
+                Response::json(200, serde_json::json!({ "id": PROFILE_ID, "name": "Steve", "properties": [] }))
+            }
+            _ => Response::empty(204),
+        }).await;
+        let provider = YggdrasilProvider::new(&server.url);
+
+        let profile = provider.profile(&session(&server)).await.unwrap();
+        assert_eq!(profile.name, "Steve");
+
+        let unknown = AuthResult { uuid: "ffff".into(), ..session(&server) };
+        assert_eq!(provider.profile(&unknown).await.unwrap_err().code, "PROFILE_FAIL");
+    }
+}
@@ -0,0 +1,351 @@
+use tauri::{command, Window};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use crate::auth::{self as providers, microsoft, vault, AuthResult, LoginRequest};
+use crate::launcher::{AuthData, MinecraftLauncher};
+use crate::error::{LauncherError, LauncherResult};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Account {
+    pub id: String,
+    /// Display name shown in the launcher (can be renamed, defaults to the username)
+    pub name: String,
+    pub username: String,
+    pub uuid: String,
+    pub xuid: Option<String>,
+    pub mode: String, // "microsoft", "offline" or "yggdrasil"
+    pub access_token: Option<String>,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    /// Yggdrasil API root for third-party accounts
+    #[serde(default)]
+    pub auth_server: Option<String>,
+    /// Milliseconds since the UNIX epoch
+    pub expires_at: Option<u64>,
+    pub added: u64,
+}
+
+/// What the frontend gets to see of an account: everything except the tokens,
+/// which stay in the credential vault and only leave it for a launch
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    pub id: String,
+    pub name: String,
+    pub username: String,
+    pub uuid: String,
+    pub xuid: Option<String>,
+    pub mode: String,
+    pub auth_server: Option<String>,
+    pub expires_at: Option<u64>,
+    pub added: u64,
+    /// Whether a token is stored (false while a passphrase vault is locked)
+    pub has_token: bool,
+}
+
+impl From<&Account> for AccountInfo {
+    fn from(account: &Account) -> Self {
+        Self {
+            id: account.id.clone(),
+            name: account.name.clone(),
+            username: account.username.clone(),
+            uuid: account.uuid.clone(),
+            xuid: account.xuid.clone(),
+            mode: account.mode.clone(),
+            auth_server: account.auth_server.clone(),
+            expires_at: account.expires_at,
+            added: account.added,
+            has_token: account.access_token.as_deref().is_some_and(|token| !token.is_empty()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct AccountStore {
+    active: Option<String>,
+    accounts: Vec<Account>,
+}
+
+impl Account {
+    /// Build the launch credentials for this account
+    pub fn to_auth_data(&self) -> AuthData {
+        match self.mode.as_str() {
+            "microsoft" => AuthData::Microsoft {
+                access_token: self.access_token.clone().unwrap_or_default(),
+                uuid: self.uuid.clone(),
+                username: self.username.clone(),
+                xuid: self.xuid.clone(),
+            },
+            "yggdrasil" => AuthData::Yggdrasil {
+                access_token: self.access_token.clone().unwrap_or_default(),
+                uuid: self.uuid.clone(),
+                username: self.username.clone(),
+                api_root: self.auth_server.clone().unwrap_or_default(),
+            },
+            _ => AuthData::Offline {
+                uuid: self.uuid.clone(),
+                username: self.username.clone(),
+                xuid: self.xuid.clone(),
+            },
+        }
+    }
+}
+
+impl Account {
+    /// The stored session in the shape the auth providers work with
+    pub fn to_session(&self) -> AuthResult {
+        let expires_in = self.expires_at
+            .map(|at| at.saturating_sub(now_millis()) / 1000)
+            .unwrap_or(0);
+
+        AuthResult {
+            access_token: self.access_token.clone().unwrap_or_default(),
+            refresh_token: self.refresh_token.clone().unwrap_or_default(),
+            expires_in,
+            username: self.username.clone(),
+            uuid: self.uuid.clone(),
+            xuid: self.xuid.clone().unwrap_or_else(|| "0".to_string()),
+            mode: self.mode.clone(),
+            auth_server: self.auth_server.clone(),
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+fn get_accounts_path() -> PathBuf {
+    let dir = MinecraftLauncher::get_launcher_data_dir();
+    if !dir.exists() {
+        let _ = fs::create_dir_all(&dir);
+    }
+    dir.join("accounts.json")
+}
+
+/// Tokens of an account as kept in the credential vault
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredTokens {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+}
+
+fn tokens_secret(id: &str) -> String {
+    format!("account:{}", id)
+}
+
+/// Load the registry and fill in tokens from the vault.
+/// While a passphrase vault is locked the accounts come back without tokens.
+fn load_store() -> LauncherResult<AccountStore> {
+    let path = get_accounts_path();
+    if !path.exists() {
+        return Ok(AccountStore::default());
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| LauncherError::io(format!("Failed to read accounts: {}", e)))?;
+    let mut store: AccountStore = serde_json::from_str(&content).map_err(|e| LauncherError::parse(format!("Failed to parse accounts: {}", e)))?;
+
+    // Registries written before the vault existed keep their tokens in plain text
+    let has_plaintext = store.accounts.iter().any(|a| a.access_token.is_some() || a.refresh_token.is_some());
+
+    for account in store.accounts.iter_mut().filter(|a| a.access_token.is_none()) {
+        match vault::get_secret(&tokens_secret(&account.id)) {
+            Ok(Some(json)) => {
+                if let Ok(tokens) = serde_json::from_str::<StoredTokens>(&json) {
+                    account.access_token = tokens.access_token;
+                    account.refresh_token = tokens.refresh_token;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                println!("[Accounts] Tokens unavailable: {}", e);
+                break;
+            }
+        }
+    }
+
+    if has_plaintext {
+        match save_store(&store) {
+            Ok(()) => println!("[Accounts] Moved account tokens into the credential vault"),
+            Err(e) => println!("[Accounts] Failed to migrate tokens into the vault: {}", e),
+        }
+    }
+    Ok(store)
+}
+
+/// Write the registry with tokens moved into the vault; accounts.json never holds them
+fn save_store(store: &AccountStore) -> LauncherResult<()> {
+    let mut on_disk = AccountStore { active: store.active.clone(), accounts: store.accounts.clone() };
+    for account in on_disk.accounts.iter_mut() {
+        let tokens = StoredTokens {
+            access_token: account.access_token.take(),
+            refresh_token: account.refresh_token.take(),
+        };
+        if tokens.access_token.is_none() && tokens.refresh_token.is_none() {
+            continue;
+        }
+        let json = serde_json::to_string(&tokens).map_err(|e| LauncherError::parse(format!("Failed to serialize tokens: {}", e)))?;
+        vault::set_secret(&tokens_secret(&account.id), &json)?;
+    }
+
+    let json = serde_json::to_string_pretty(&on_disk)
+        .map_err(|e| LauncherError::parse(format!("Failed to serialize accounts: {}", e)))?;
+    fs::write(get_accounts_path(), json).map_err(|e| LauncherError::io(format!("Failed to write accounts: {}", e)))
+}
+
+/// Insert or update the account for a login result and make it the active one
+pub(crate) fn upsert_from_auth(result: &AuthResult) -> LauncherResult<Account> {
+    store_auth(result, true)
+}
+
+/// Store refreshed credentials without changing which account is active
+pub(crate) fn update_from_auth(result: &AuthResult) -> LauncherResult<Account> {
+    store_auth(result, false)
+}
+
+fn store_auth(result: &AuthResult, activate: bool) -> LauncherResult<Account> {
+    let mut store = load_store()?;
+    let expires_at = if result.mode == "microsoft" {
+        Some(now_millis() + result.expires_in * 1000)
+    } else {
+        None
+    };
+
+    let account = match store.accounts.iter_mut().find(|a| a.id == result.uuid) {
+        Some(existing) => {
+            existing.username = result.username.clone();
+            existing.xuid = Some(result.xuid.clone());
+            existing.mode = result.mode.clone();
+            existing.access_token = Some(result.access_token.clone());
+            existing.refresh_token = Some(result.refresh_token.clone());
+            existing.auth_server = result.auth_server.clone();
+            existing.expires_at = expires_at;
+            existing.clone()
+        }
+        None => {
+            let account = Account {
+                id: result.uuid.clone(),
+                name: result.username.clone(),
+                username: result.username.clone(),
+                uuid: result.uuid.clone(),
+                xuid: Some(result.xuid.clone()),
+                mode: result.mode.clone(),
+                access_token: Some(result.access_token.clone()),
+                refresh_token: Some(result.refresh_token.clone()),
+                auth_server: result.auth_server.clone(),
+                expires_at,
+                added: now_millis(),
+            };
+            store.accounts.push(account.clone());
+            account
+        }
+    };
+
+    if activate {
+        store.active = Some(account.id.clone());
+    }
+    save_store(&store)?;
+    println!("[Accounts] Stored account {} ({})", account.username, account.mode);
+    Ok(account)
+}
+
+pub(crate) fn find_account(id: &str) -> LauncherResult<Option<Account>> {
+    let store = load_store()?;
+    Ok(store.accounts.into_iter().find(|a| a.id == id))
+}
+
+pub(crate) fn find_active_account() -> LauncherResult<Option<Account>> {
+    let store = load_store()?;
+    let active = match store.active {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+    Ok(store.accounts.into_iter().find(|a| a.id == active))
+}
+
+#[command]
+pub async fn list_accounts() -> LauncherResult<Vec<AccountInfo>> {
+    Ok(load_store()?.accounts.iter().map(AccountInfo::from).collect())
+}
+
+#[command]
+pub async fn get_active_account() -> LauncherResult<Option<AccountInfo>> {
+    Ok(find_active_account()?.as_ref().map(AccountInfo::from))
+}
+
+/// Log in a new account and add it to the registry.
+/// `mode` is "microsoft" (device code flow), "microsoft_browser" (browser login), "offline" (requires `username`)
+/// or "yggdrasil" (requires `server_url`, `username` and `password`).
+#[command]
+pub async fn add_account(window: Window, mode: String, username: Option<String>, password: Option<String>, server_url: Option<String>) -> LauncherResult<AccountInfo> {
+    let provider = providers::provider_for(&mode, server_url.as_deref(), Some(window))?;
+    let result = provider.login(LoginRequest { username, password }).await?;
+    Ok(AccountInfo::from(&upsert_from_auth(&result)?))
+}
+
+#[command]
+pub async fn remove_account(id: String) -> LauncherResult<()> {
+    let mut store = load_store()?;
+    let removed = store.accounts.iter().find(|a| a.id == id).cloned();
+    if removed.is_none() {
+        return Err(LauncherError::Auth { code: "ACCOUNT_NOT_FOUND".into(), message: "Account not found".into() });
+    }
+    store.accounts.retain(|a| a.id != id);
+
+    if store.active.as_deref() == Some(id.as_str()) {
+        store.active = store.accounts.first().map(|a| a.id.clone());
+    }
+    save_store(&store)?;
+
+    // Best effort: revoke the token server-side and drop any persisted session
+    if let Some(account) = removed {
+        if let Ok(provider) = providers::provider_for(&account.mode, account.auth_server.as_deref(), None) {
+            if let Err(e) = provider.invalidate(&account.to_session()).await {
+                println!("[Accounts] Failed to invalidate session for {}: {}", account.username, e.message);
+            }
+        }
+    }
+    microsoft::delete_session(&id);
+    let _ = vault::remove_secret(&tokens_secret(&id));
+
+    println!("[Accounts] Removed account {}", id);
+    Ok(())
+}
+
+#[command]
+pub async fn set_active_account(id: String) -> LauncherResult<AccountInfo> {
+    let mut store = load_store()?;
+    let account = store.accounts.iter()
+        .find(|a| a.id == id)
+        .cloned()
+        .ok_or_else(|| LauncherError::Auth { code: "ACCOUNT_NOT_FOUND".into(), message: "Account not found".into() })?;
+
+    store.active = Some(id);
+    save_store(&store)?;
+    Ok(AccountInfo::from(&account))
+}
+
+#[command]
+pub async fn rename_account(id: String, name: String) -> LauncherResult<AccountInfo> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(LauncherError::Auth { code: "INVALID_ACCOUNT_NAME".into(), message: "Account name cannot be empty".into() });
+    }
+
+    let mut store = load_store()?;
+    let account = store.accounts.iter_mut()
+        .find(|a| a.id == id)
+        .ok_or_else(|| LauncherError::Auth { code: "ACCOUNT_NOT_FOUND".into(), message: "Account not found".into() })?;
+
+    account.name = name;
+    let account = AccountInfo::from(&*account);
+    save_store(&store)?;
+    Ok(account)
+}
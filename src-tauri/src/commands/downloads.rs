@@ -0,0 +1,47 @@
+use tauri::{command, State};
+use crate::launcher::download_manager::{DownloadJob, DownloadManager, DownloadProgress};
+use crate::error::LauncherResult;
+
+#[command]
+pub async fn list_downloads(downloads: State<'_, DownloadManager>) -> LauncherResult<Vec<DownloadJob>> {
+    Ok(downloads.jobs())
+}
+
+#[command]
+pub async fn get_download_progress(downloads: State<'_, DownloadManager>) -> LauncherResult<DownloadProgress> {
+    Ok(downloads.progress())
+}
+
+/// Stop a download but keep what was already received; `resume_download` continues from there
+#[command]
+pub async fn pause_download(downloads: State<'_, DownloadManager>, id: u64) -> LauncherResult<()> {
+    downloads.pause(id)
+}
+
+#[command]
+pub async fn resume_download(downloads: State<'_, DownloadManager>, id: u64) -> LauncherResult<()> {
+    downloads.resume(id)
+}
+
+#[command]
+pub async fn cancel_download(downloads: State<'_, DownloadManager>, id: u64) -> LauncherResult<()> {
+    downloads.cancel(id)
+}
+
+#[command]
+pub async fn pause_all_downloads(downloads: State<'_, DownloadManager>) -> LauncherResult<()> {
+    downloads.pause_all();
+    Ok(())
+}
+
+#[command]
+pub async fn resume_all_downloads(downloads: State<'_, DownloadManager>) -> LauncherResult<()> {
+    downloads.resume_all();
+    Ok(())
+}
+
+#[command]
+pub async fn cancel_all_downloads(downloads: State<'_, DownloadManager>) -> LauncherResult<()> {
+    downloads.cancel_all();
+    Ok(())
+}
@@ -2,16 +2,17 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 use unrar::Archive;
+use crate::error::{LauncherError, LauncherResult};
 
 #[tauri::command]
-pub async fn extract_zip(zip_path: String, target_dir: String, skip_files: Option<Vec<String>>) -> Result<(), String> {
+pub async fn extract_zip(zip_path: String, target_dir: String, skip_files: Option<Vec<String>>) -> LauncherResult<()> {
     let skip_list = skip_files.unwrap_or_default();
 
     // Handle RAR files
     if zip_path.to_lowercase().ends_with(".rar") {
         let mut archive = Archive::new(&zip_path)
             .open_for_processing()
-            .map_err(|e| format!("Failed to open RAR: {}", e))?;
+            .map_err(|e| LauncherError::io(format!("Failed to open RAR: {}", e)))?;
 
         loop {
             match archive.read_header() {
@@ -23,41 +24,41 @@ pub async fn extract_zip(zip_path: String, target_dir: String, skip_files: Optio
                     // Check if we should skip this file
                     let file_name_str = filename.file_name().unwrap_or_default().to_string_lossy();
                     if skip_list.iter().any(|s| s == &file_name_str) && dest_path.exists() {
-                        archive = header.skip().map_err(|e| format!("Failed to skip entry: {}", e))?;
+                        archive = header.skip().map_err(|e| LauncherError::io(format!("Failed to skip entry: {}", e)))?;
                         continue;
                     }
 
                     if entry.is_directory() {
-                        fs::create_dir_all(&dest_path).map_err(|e| format!("Failed to create dir {:?}: {}", dest_path, e))?;
-                        archive = header.skip().map_err(|e| format!("Failed to skip dir: {}", e))?;
+                        fs::create_dir_all(&dest_path).map_err(|e| LauncherError::io(format!("Failed to create dir {:?}: {}", dest_path, e)))?;
+                        archive = header.skip().map_err(|e| LauncherError::io(format!("Failed to skip dir: {}", e)))?;
                     } else {
                         // Ensure parent directories exist
                         if let Some(parent) = dest_path.parent() {
                             if !parent.exists() {
-                                fs::create_dir_all(parent).map_err(|e| format!("Failed to create parent dir {:?}: {}", parent, e))?;
+                                fs::create_dir_all(parent).map_err(|e| LauncherError::io(format!("Failed to create parent dir {:?}: {}", parent, e)))?;
                             }
                         }
 
                         // Read content into memory and write manually to avoid unrar creation issues
-                        let (data, next_archive) = header.read().map_err(|e| format!("Failed to read entry {:?}: {}", filename, e))?;
+                        let (data, next_archive) = header.read().map_err(|e| LauncherError::io(format!("Failed to read entry {:?}: {}", filename, e)))?;
                         archive = next_archive;
 
-                        fs::write(&dest_path, data).map_err(|e| format!("Failed to write file {:?}: {}", dest_path, e))?;
+                        fs::write(&dest_path, data).map_err(|e| LauncherError::io(format!("Failed to write file {:?}: {}", dest_path, e)))?;
                     }
                 }
                 Ok(None) => break,
-                Err(e) => return Err(format!("RAR read error: {}", e)),
+                Err(e) => return Err(LauncherError::io(format!("RAR read error: {}", e))),
             }
         }
         return Ok(());
     }
 
     // Handle ZIP files
-    let file = fs::File::open(&zip_path).map_err(|e| e.to_string())?;
-    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let file = fs::File::open(&zip_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
 
     for i in 0..archive.len() {
-        let mut file = archive.by_index(i).map_err(|e| e.to_string())?;
+        let mut file = archive.by_index(i)?;
         let outpath = match file.enclosed_name() {
             Some(path) => Path::new(&target_dir).join(path),
             None => continue,
@@ -70,15 +71,15 @@ pub async fn extract_zip(zip_path: String, target_dir: String, skip_files: Optio
         }
 
         if (*file.name()).ends_with('/') {
-            fs::create_dir_all(&outpath).map_err(|e| e.to_string())?;
+            fs::create_dir_all(&outpath)?;
         } else {
             if let Some(p) = outpath.parent() {
                 if !p.exists() {
-                    fs::create_dir_all(p).map_err(|e| e.to_string())?;
+                    fs::create_dir_all(p)?;
                 }
             }
-            let mut outfile = fs::File::create(&outpath).map_err(|e| e.to_string())?;
-            io::copy(&mut file, &mut outfile).map_err(|e| e.to_string())?;
+            let mut outfile = fs::File::create(&outpath)?;
+            io::copy(&mut file, &mut outfile)?;
         }
     }
 
@@ -86,18 +87,18 @@ pub async fn extract_zip(zip_path: String, target_dir: String, skip_files: Optio
 }
 
 #[tauri::command]
-pub async fn read_text_file(path: String) -> Result<String, String> {
-    fs::read_to_string(path).map_err(|e| e.to_string())
+pub async fn read_text_file(path: String) -> LauncherResult<String> {
+    fs::read_to_string(path).map_err(LauncherError::from)
 }
 
 #[tauri::command]
-pub async fn read_binary_file(path: String) -> Result<Vec<u8>, String> {
-    fs::read(path).map_err(|e| e.to_string())
+pub async fn read_binary_file(path: String) -> LauncherResult<Vec<u8>> {
+    fs::read(path).map_err(LauncherError::from)
 }
 
 #[tauri::command]
-pub async fn write_text_file(path: String, content: String) -> Result<(), String> {
-    fs::write(path, content).map_err(|e| e.to_string())
+pub async fn write_text_file(path: String, content: String) -> LauncherResult<()> {
+    fs::write(path, content).map_err(LauncherError::from)
 }
 
 #[tauri::command]
@@ -112,12 +113,12 @@ pub struct FileEntry {
 }
 
 #[tauri::command]
-pub async fn list_files(path: String) -> Result<Vec<FileEntry>, String> {
+pub async fn list_files(path: String) -> LauncherResult<Vec<FileEntry>> {
     let mut entries = Vec::new();
-    let dir = fs::read_dir(path).map_err(|e| e.to_string())?;
+    let dir = fs::read_dir(path)?;
     
     for entry in dir {
-        let entry = entry.map_err(|e| e.to_string())?;
+        let entry = entry?;
         let path = entry.path();
         let name = path.file_name().unwrap().to_string_lossy().to_string();
         let is_dir = path.is_dir();
@@ -129,14 +130,14 @@ pub async fn list_files(path: String) -> Result<Vec<FileEntry>, String> {
 }
 
 #[tauri::command]
-pub async fn delete_file(path: String) -> Result<(), String> {
-    fs::remove_file(path).map_err(|e| e.to_string())
+pub async fn delete_file(path: String) -> LauncherResult<()> {
+    fs::remove_file(path).map_err(LauncherError::from)
 }
 
 #[tauri::command]
-pub async fn get_mod_icon(path: String) -> Result<Vec<u8>, String> {
-    let file = fs::File::open(&path).map_err(|e| e.to_string())?;
-    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+pub async fn get_mod_icon(path: String) -> LauncherResult<Vec<u8>> {
+    let file = fs::File::open(&path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
 
     // Try to find fabric.mod.json
     let icon_path = {
@@ -177,7 +178,7 @@ pub async fn get_mod_icon(path: String) -> Result<Vec<u8>, String> {
         }
     }
 
-    Err("Icon not found".to_string())
+    Err(LauncherError::io("Icon not found"))
 }
 
 #[derive(serde::Serialize)]
@@ -197,9 +198,9 @@ fn extract_toml_value(line: &str) -> Option<String> {
 }
 
 #[tauri::command]
-pub async fn get_mod_metadata(path: String) -> Result<ModMetadata, String> {
-    let file = fs::File::open(&path).map_err(|e| e.to_string())?;
-    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+pub async fn get_mod_metadata(path: String) -> LauncherResult<ModMetadata> {
+    let file = fs::File::open(&path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
 
     // 1. Try Fabric (fabric.mod.json)
     if let Ok(mut file) = archive.by_name("fabric.mod.json") {
@@ -250,26 +251,26 @@ pub async fn get_mod_metadata(path: String) -> Result<ModMetadata, String> {
 }
 
 #[tauri::command]
-pub async fn run_installer(path: String) -> Result<(), String> {
-    open::that(path).map_err(|e| e.to_string())
+pub async fn run_installer(path: String) -> LauncherResult<()> {
+    open::that(path).map_err(LauncherError::from)
 }
 
 #[tauri::command]
-pub async fn move_file(source: String, target: String) -> Result<(), String> {
-    fs::rename(source, target).map_err(|e| e.to_string())
+pub async fn move_file(source: String, target: String) -> LauncherResult<()> {
+    fs::rename(source, target).map_err(LauncherError::from)
 }
 
 #[tauri::command]
-pub async fn rename_file(path: String, new_name: String) -> Result<(), String> {
+pub async fn rename_file(path: String, new_name: String) -> LauncherResult<()> {
     let path_buf = PathBuf::from(&path);
-    let parent = path_buf.parent().ok_or("Invalid path")?;
+    let parent = path_buf.parent().ok_or_else(|| LauncherError::io_at(&path_buf, "Invalid path"))?;
     let new_path = parent.join(new_name);
-    fs::rename(path, new_path).map_err(|e| e.to_string())
+    fs::rename(path, new_path).map_err(LauncherError::from)
 }
 
 #[tauri::command]
-pub async fn remove_dir(path: String) -> Result<(), String> {
-    fs::remove_dir_all(path).map_err(|e| e.to_string())
+pub async fn remove_dir(path: String) -> LauncherResult<()> {
+    fs::remove_dir_all(path).map_err(LauncherError::from)
 }
 
 fn merge_dir_recursive(source: &std::path::Path, target: &std::path::Path, skip_files: &[String], is_root: bool) -> io::Result<()> {
@@ -309,10 +310,10 @@ fn merge_dir_recursive(source: &std::path::Path, target: &std::path::Path, skip_
 }
 
 #[tauri::command]
-pub async fn merge_dir(source: String, target: String, skip_files: Option<Vec<String>>) -> Result<(), String> {
+pub async fn merge_dir(source: String, target: String, skip_files: Option<Vec<String>>) -> LauncherResult<()> {
     let source_path = PathBuf::from(&source);
     let target_path = PathBuf::from(&target);
     let skip = skip_files.unwrap_or_default();
     
-    merge_dir_recursive(&source_path, &target_path, &skip, true).map_err(|e| e.to_string())
+    merge_dir_recursive(&source_path, &target_path, &skip, true).map_err(LauncherError::from)
 }
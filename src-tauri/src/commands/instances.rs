@@ -1,8 +1,11 @@
-use tauri::command;
+use tauri::{command, AppHandle, Emitter, State};
 use serde::{Serialize, Deserialize};
 use std::fs;
 use std::path::PathBuf;
-use crate::launcher::MinecraftLauncher;
+use crate::launcher::{LaunchCustomization, MinecraftLauncher};
+use crate::launcher::content_store::{self, MigrationProgress, MigrationReport};
+use crate::launcher::process_registry::ProcessRegistry;
+use crate::error::{LauncherError, LauncherResult};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -19,15 +22,19 @@ pub struct Instance {
     #[serde(alias = "background_image")]
     pub background_image: Option<String>,
     pub created: u64,
+    /// Account used to launch this instance when the launch options don't name one
+    #[serde(default, alias = "default_account")]
+    pub default_account: Option<String>,
+    /// Extra JVM/game arguments, environment, wrapper command and hooks
+    #[serde(flatten)]
+    pub customization: LaunchCustomization,
 }
 
 fn get_instances_dir() -> PathBuf {
-    let mut path = MinecraftLauncher::get_default_minecraft_dir();
+    let mut path = MinecraftLauncher::get_launcher_data_dir();
     // Use a custom subdirectory for our launcher's instances to avoid cluttering .minecraft root if possible,
     // or just use .minecraft/instances if we want to be standard-ish.
     // Let's use .porcos/instances to be safe and separate.
-    path.pop(); // Go up from .minecraft
-    path.push(".porcos");
     path.push("instances");
     
     if !path.exists() {
@@ -37,7 +44,7 @@ fn get_instances_dir() -> PathBuf {
 }
 
 #[command]
-pub async fn create_instance(name: String, version: String, mod_loader: Option<String>, mod_loader_version: Option<String>, image_path: Option<String>) -> Result<Instance, String> {
+pub async fn create_instance(name: String, version: String, mod_loader: Option<String>, mod_loader_version: Option<String>, image_path: Option<String>) -> LauncherResult<Instance> {
     println!("[Command] create_instance called");
     println!("[Command] Name: {}, Version: {}, Loader: {:?}, LoaderVer: {:?}", name, version, mod_loader, mod_loader_version);
     
@@ -51,7 +58,7 @@ pub async fn create_instance(name: String, version: String, mod_loader: Option<S
     let instance_dir = instances_dir.join(&id);
     
     if let Err(e) = fs::create_dir_all(&instance_dir) {
-        return Err(format!("Failed to create instance directory: {}", e));
+        return Err(LauncherError::io_at(&instance_dir, format!("Failed to create instance directory: {}", e)));
     }
 
     let mut background_image = None;
@@ -81,21 +88,23 @@ pub async fn create_instance(name: String, version: String, mod_loader: Option<S
         icon: None,
         background_image,
         created,
+        default_account: None,
+        customization: LaunchCustomization::default(),
     };
 
     let config_path = instance_dir.join("instance.json");
     let config_json = serde_json::to_string_pretty(&instance)
-        .map_err(|e| format!("Failed to serialize instance config: {}", e))?;
+        .map_err(|e| LauncherError::parse(format!("Failed to serialize instance config: {}", e)))?;
 
-    if let Err(e) = fs::write(config_path, config_json) {
-        return Err(format!("Failed to write instance config: {}", e));
+    if let Err(e) = fs::write(&config_path, config_json) {
+        return Err(LauncherError::io_at(&config_path, format!("Failed to write instance config: {}", e)));
     }
 
     Ok(instance)
 }
 
 #[command]
-pub async fn get_instances() -> Result<Vec<Instance>, String> {
+pub async fn get_instances() -> LauncherResult<Vec<Instance>> {
     println!("[Command] get_instances called");
     let instances_dir = get_instances_dir();
     let mut instances = Vec::new();
@@ -126,7 +135,11 @@ pub async fn get_instances() -> Result<Vec<Instance>, String> {
 }
 
 #[command]
-pub async fn delete_instance(id: String) -> Result<(), String> {
+pub async fn delete_instance(processes: State<'_, ProcessRegistry>, id: String) -> LauncherResult<()> {
+    if processes.is_running(&id) || processes.is_launching(&id) {
+        return Err(LauncherError::instance(Some(&id), "The instance is running. Close the game before deleting it."));
+    }
+
     let instances_dir = get_instances_dir();
     let instance_dir = instances_dir.join(&id);
 
@@ -148,18 +161,71 @@ pub async fn delete_instance(id: String) -> Result<(), String> {
     match fs::rename(&instance_dir, &trash_path) {
         Ok(_) => {
             // Rename succeeded, now delete the trash folder
-            fs::remove_dir_all(&trash_path).map_err(|e| format!("Instance removed from list, but failed to clean up files: {}", e))?;
+            fs::remove_dir_all(&trash_path).map_err(|e| LauncherError::io(format!("Instance removed from list, but failed to clean up files: {}", e)))?;
         }
         Err(e) => {
             // Rename failed (maybe cross-device link or locked), try direct delete
             println!("Rename failed ({}), trying direct delete...", e);
-            fs::remove_dir_all(&instance_dir).map_err(|e| format!("Failed to delete instance. Is it running? Error: {}", e))?;
+            fs::remove_dir_all(&instance_dir).map_err(|e| LauncherError::io(format!("Failed to delete instance. Is it running? Error: {}", e)))?;
         }
     }
 
     Ok(())
 }
 
+/// Move versions, libraries and assets downloaded into instance folders to the shared store.
+/// Migrates one instance, or all of them when no id is given. Reports `content-migration-progress`.
+#[command]
+pub async fn migrate_instance_content(app: AppHandle, id: Option<String>) -> LauncherResult<MigrationReport> {
+    println!("[Command] migrate_instance_content called for {:?}", id);
+    tauri::async_runtime::spawn_blocking(move || migrate_instances(id.as_deref(), Some(&app)))
+        .await
+        .map_err(|e| LauncherError::Other(format!("Migration task failed: {}", e)))?
+}
+
+/// Blocking: migrates every instance unless a previous startup already did.
+/// Later downloads go straight to the shared store, so once is enough.
+pub fn migrate_instances_once(app: &AppHandle) -> LauncherResult<()> {
+    let marker = MinecraftLauncher::get_shared_content_dir().join(".migrated");
+    if marker.exists() {
+        return Ok(());
+    }
+    migrate_instances(None, Some(app))?;
+    if let Some(parent) = marker.parent() {
+        fs::create_dir_all(parent).map_err(|e| LauncherError::io_at(parent, format!("Failed to create content dir: {}", e)))?;
+    }
+    fs::write(&marker, b"").map_err(|e| LauncherError::io_at(&marker, format!("Failed to write migration marker: {}", e)))
+}
+
+/// Blocking: hashes every file that already exists in the shared store
+pub fn migrate_instances(id: Option<&str>, app: Option<&AppHandle>) -> LauncherResult<MigrationReport> {
+    let instances_dir = get_instances_dir();
+    let content_dir = MinecraftLauncher::get_shared_content_dir();
+    let mut report = MigrationReport::default();
+
+    let instance_dirs: Vec<PathBuf> = match id {
+        Some(id) => vec![instances_dir.join(id)],
+        None => fs::read_dir(&instances_dir)
+            .map_err(|e| LauncherError::io_at(&instances_dir, format!("Failed to read instances: {}", e)))?
+            .flatten()
+            .map(|entry| entry.path())
+            // Skip half-deleted instances
+            .filter(|path| path.is_dir() && !path.file_name().is_some_and(|name| name.to_string_lossy().starts_with(".trash_")))
+            .collect(),
+    };
+
+    let mut on_progress = |progress: &MigrationProgress| {
+        println!("[ContentStore] {}: {}/{} files", progress.game_dir, progress.processed, progress.total);
+        if let Some(app) = app {
+            let _ = app.emit("content-migration-progress", progress);
+        }
+    };
+    for instance_dir in instance_dirs {
+        report.merge(content_store::migrate_game_dir(&instance_dir, &content_dir, &mut on_progress)?);
+    }
+    Ok(report)
+}
+
 #[command]
 pub fn get_instance_path(id: String) -> String {
     let instances_dir = get_instances_dir();
@@ -167,36 +233,39 @@ pub fn get_instance_path(id: String) -> String {
 }
 
 #[command]
-pub async fn open_instance_folder(id: String) -> Result<(), String> {
+pub async fn open_instance_folder(id: String) -> LauncherResult<()> {
     let instances_dir = get_instances_dir();
     let instance_dir = instances_dir.join(&id);
     
     if instance_dir.exists() {
-        open::that(instance_dir).map_err(|e| format!("Failed to open folder: {}", e))?;
+        open::that(instance_dir).map_err(|e| LauncherError::io(format!("Failed to open folder: {}", e)))?;
         Ok(())
     } else {
-        Err("Instance folder not found".to_string())
+        Err(LauncherError::instance(Some(&id), "Instance folder not found"))
     }
 }
 
 #[command]
-pub async fn update_instance(id: String, name: Option<String>, version: Option<String>, versions: Option<Vec<String>>, mod_loader: Option<String>, mod_loader_version: Option<String>, icon: Option<String>, background_image: Option<String>) -> Result<Instance, String> {
+pub async fn update_instance(id: String, name: Option<String>, version: Option<String>, versions: Option<Vec<String>>, mod_loader: Option<String>, mod_loader_version: Option<String>, icon: Option<String>, background_image: Option<String>, default_account: Option<String>, launch_settings: Option<LaunchCustomization>) -> LauncherResult<Instance> {
     let instances_dir = get_instances_dir();
     let instance_dir = instances_dir.join(&id);
     let config_path = instance_dir.join("instance.json");
 
     if !config_path.exists() {
-        return Err("Instance not found".to_string());
+        return Err(LauncherError::instance(Some(&id), "Instance not found"));
     }
 
-    let content = fs::read_to_string(&config_path).map_err(|e| format!("Failed to read config: {}", e))?;
-    let mut instance: Instance = serde_json::from_str(&content).map_err(|e| format!("Failed to parse config: {}", e))?;
+    let content = fs::read_to_string(&config_path).map_err(|e| LauncherError::io(format!("Failed to read config: {}", e)))?;
+    let mut instance: Instance = serde_json::from_str(&content).map_err(|e| LauncherError::parse(format!("Failed to parse config: {}", e)))?;
 
     if let Some(n) = name { instance.name = n; }
     if let Some(v) = version { instance.version = v; }
     if let Some(vs) = versions { instance.versions = Some(vs); }
     if let Some(ml) = mod_loader { instance.mod_loader = Some(ml); }
     if let Some(mlv) = mod_loader_version { instance.mod_loader_version = Some(mlv); }
+    // An empty string clears the pinned account
+    if let Some(acc) = default_account { instance.default_account = if acc.is_empty() { None } else { Some(acc) }; }
+    if let Some(settings) = launch_settings { instance.customization = settings; }
     
     // Handle Icon
     if let Some(i) = icon {
@@ -244,10 +313,10 @@ pub async fn update_instance(id: String, name: Option<String>, version: Option<S
     }
 
     let config_json = serde_json::to_string_pretty(&instance)
-        .map_err(|e| format!("Failed to serialize instance config: {}", e))?;
+        .map_err(|e| LauncherError::parse(format!("Failed to serialize instance config: {}", e)))?;
 
-    if let Err(e) = fs::write(config_path, config_json) {
-        return Err(format!("Failed to write instance config: {}", e));
+    if let Err(e) = fs::write(&config_path, config_json) {
+        return Err(LauncherError::io_at(&config_path, format!("Failed to write instance config: {}", e)));
     }
 
     Ok(instance)
@@ -1,29 +1,120 @@
-use tauri::command;
-use crate::launcher::{MinecraftLauncher, LaunchOptions, LaunchResult, VersionManager, MinecraftVersion};
-// use std::path::PathBuf;
+use tauri::{command, Emitter, State, Window};
+use crate::launcher::{MinecraftLauncher, LaunchOptions, LaunchResult, VersionManager, MinecraftVersion, AuthData, AuthlibInjectorConfig};
+use crate::launcher::console::{ConsoleFilter, ConsoleHistory, ConsolePage, LogLevel};
+use crate::launcher::download_manager::DownloadManager;
+use crate::launcher::integrity::VerifyReport;
+use crate::launcher::launch_plan::LaunchPlan;
+use crate::launcher::launch_task::{LaunchRegistry, LaunchTask};
+use crate::launcher::process_registry::{ProcessRegistry, RunningGame};
+use crate::launcher::yggdrasil_server::{OfflineProfile, YggdrasilState};
+use crate::commands::accounts::{self, Account};
+use crate::commands::instances::Instance;
+use crate::auth::{self as providers, services, token};
+use crate::error::{LauncherError, LauncherResult};
+use std::path::PathBuf;
 
 #[command]
-pub async fn get_available_versions() -> Result<Vec<MinecraftVersion>, String> {
+pub async fn get_available_versions() -> LauncherResult<Vec<MinecraftVersion>> {
     println!("[Command] get_available_versions called");
     
-    let cache_dir = MinecraftLauncher::get_default_minecraft_dir().join("versions");
+    let cache_dir = MinecraftLauncher::get_shared_content_dir().join("versions");
     let version_manager = VersionManager::new(cache_dir);
     
     version_manager.get_release_versions().await
 }
 
 #[command]
-pub async fn launch_minecraft(window: tauri::Window, options: serde_json::Value) -> Result<LaunchResult, String> {
+pub async fn launch_minecraft(
+    window: Window,
+    yggdrasil: State<'_, YggdrasilState>,
+    launches: State<'_, LaunchRegistry>,
+    options: serde_json::Value,
+) -> LauncherResult<LaunchResult> {
     println!("[Command] launch_minecraft called");
-    println!("[Command] Raw Options: {}", serde_json::to_string_pretty(&options).unwrap_or_default());
-    
+    let task = LaunchTask::new(Some(window.clone()));
+    let _registration = launches.register(&task);
+
+    let (minecraft_dir, options, authlib_injector) = prepare_options(&window, &yggdrasil, options, true).await?;
+
+    task.check()?;
+    let mut launcher = MinecraftLauncher::new(minecraft_dir, Some(window)).with_task(task);
+    if let Some(content_dir) = options.content_dir.clone() {
+        launcher = launcher.with_content_dir(content_dir);
+    }
+    if let Some(config) = authlib_injector {
+        launcher = launcher.with_authlib_injector(config);
+    }
+
+    launcher.launch(options).await
+}
+
+/// Resolve a launch (version, loader, libraries, Java and arguments) without downloading
+/// anything or starting the game. Access tokens are redacted from the returned arguments.
+#[command]
+pub async fn prepare_launch(
+    window: Window,
+    yggdrasil: State<'_, YggdrasilState>,
+    options: serde_json::Value,
+) -> LauncherResult<LaunchPlan> {
+    println!("[Command] prepare_launch called");
+    let plan = dry_run(&window, &yggdrasil, options).await?;
+    Ok(plan.redacted())
+}
+
+/// Write the launch plan as a script (`.bat` on Windows, `sh` elsewhere) that starts the game
+/// by hand. The access token is not written; the script reads it from `MC_ACCESS_TOKEN`.
+#[command]
+pub async fn export_launch_script(
+    window: Window,
+    yggdrasil: State<'_, YggdrasilState>,
+    options: serde_json::Value,
+    path: String,
+) -> LauncherResult<String> {
+    println!("[Command] export_launch_script called: {}", path);
+    let plan = dry_run(&window, &yggdrasil, options).await?;
+    let path = PathBuf::from(path);
+    std::fs::write(&path, plan.shell_script())
+        .map_err(|e| LauncherError::io_at(&path, format!("Failed to write launch script: {}", e)))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| LauncherError::io_at(&path, format!("Failed to make launch script executable: {}", e)))?;
+    }
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+async fn dry_run(window: &Window, yggdrasil: &YggdrasilState, options: serde_json::Value) -> LauncherResult<LaunchPlan> {
+    let (minecraft_dir, options, authlib_injector) = prepare_options(window, yggdrasil, options, false).await?;
+    // No window: planning must not drive the launch progress bar
+    let mut launcher = MinecraftLauncher::new(minecraft_dir, None);
+    if let Some(content_dir) = options.content_dir.clone() {
+        launcher = launcher.with_content_dir(content_dir);
+    }
+    if let Some(config) = authlib_injector {
+        launcher = launcher.with_authlib_injector(config);
+    }
+    launcher.plan(&options, false).await
+}
+
+/// Parse the frontend's launch options and fill in the game directory, the account
+/// and, for the embedded Yggdrasil server, the authlib-injector setup.
+/// Without `launching` (dry runs) the session is not refreshed and the server is not started.
+async fn prepare_options(
+    window: &Window,
+    yggdrasil: &YggdrasilState,
+    options: serde_json::Value,
+    launching: bool,
+) -> LauncherResult<(PathBuf, LaunchOptions, Option<AuthlibInjectorConfig>)> {
     // Debug keys
     if let Some(obj) = options.as_object() {
         println!("[Command] Keys present: {:?}", obj.keys().collect::<Vec<_>>());
     }
 
-    let options: LaunchOptions = serde_json::from_value(options.clone())
-        .map_err(|e| format!("Failed to parse options: {}", e))?;
+    let mut options: LaunchOptions = serde_json::from_value(options.clone())
+        .map_err(|e| LauncherError::parse(format!("Failed to parse options: {}", e)))?;
 
     println!("[Command] Parsed Options: {:?}", options);
     println!("[Command] Version: {}", options.version);
@@ -34,10 +125,16 @@ pub async fn launch_minecraft(window: tauri::Window, options: serde_json::Value)
     
     // Debug & Fallback: Check instance.json on disk
     let config_path = minecraft_dir.join("instance.json");
+    let mut instance_account = None;
     if config_path.exists() {
         match std::fs::read_to_string(&config_path) {
             Ok(content) => {
                 println!("[Command] Instance Config on Disk: {}", content);
+                if let Ok(instance) = serde_json::from_str::<Instance>(&content) {
+                    instance_account = instance.default_account;
+                    // Per-launch values win over the instance's JVM args, wrapper and hooks
+                    options.customization = options.customization.with_defaults(&instance.customization);
+                }
                 
                 // REMOVED FALLBACK: We no longer auto-apply modLoader from disk if options are missing.
                 // This allows launching Vanilla versions (options.mod_loader = None) even if the instance
@@ -48,8 +145,185 @@ pub async fn launch_minecraft(window: tauri::Window, options: serde_json::Value)
         }
     }
 
-    let launcher = MinecraftLauncher::new(minecraft_dir, Some(window));
-    launcher.launch(options).await
+    if options.auth.is_none() {
+        options.auth = Some(resolve_account(options.account_id.as_deref(), instance_account.as_deref())?.to_auth_data());
+    }
+
+    if launching {
+        if let Some(auth) = options.auth.take() {
+            options.auth = Some(ensure_fresh_auth(window, auth).await?);
+        }
+    }
+
+    let mut authlib_injector = None;
+    if options.local_yggdrasil {
+        if let Some(AuthData::Offline { uuid, username, .. }) = &options.auth {
+            let server = if launching {
+                yggdrasil.get_or_start().await?
+            } else {
+                match yggdrasil.running().await {
+                    Some(server) => server,
+                    None => return Ok((minecraft_dir, options, None)),
+                }
+            };
+            server.register_profile(OfflineProfile { uuid: uuid.clone(), name: username.clone() });
+            authlib_injector = Some(AuthlibInjectorConfig {
+                api_root: server.api_root().to_string(),
+                access_token: Some(server.issue_token(uuid)),
+                user_properties: Some(server.user_properties(uuid)),
+            });
+        }
+    }
+
+    Ok((minecraft_dir, options, authlib_injector))
+}
+
+/// Check an installed version's files against their checksums and re-download broken ones.
+/// `content_dir` is where versions, libraries and assets live (defaults to the shared store).
+#[command]
+pub async fn verify_version(
+    window: Window,
+    launches: State<'_, LaunchRegistry>,
+    version: String,
+    content_dir: Option<String>,
+) -> LauncherResult<VerifyReport> {
+    println!("[Command] verify_version called for {}", version);
+    let content_dir = content_dir.map(PathBuf::from)
+        .unwrap_or_else(MinecraftLauncher::get_shared_content_dir);
+    let launcher = MinecraftLauncher::new(content_dir.clone(), Some(window)).with_content_dir(content_dir);
+    let _registration = launches.register(launcher.task());
+    let task = launcher.task().clone();
+    task.cancellable(launcher.verify_and_repair(&version)).await
+}
+
+/// Abort a launch (or verification) that is still preparing the game, together with its
+/// downloads. `launch_id` comes from the `launch-progress` events; without it every
+/// running launch is cancelled. Returns the ids that were cancelled.
+#[command]
+pub async fn cancel_launch(
+    launches: State<'_, LaunchRegistry>,
+    downloads: State<'_, DownloadManager>,
+    launch_id: Option<String>,
+) -> LauncherResult<Vec<String>> {
+    let cancelled = launches.cancel(launch_id.as_deref());
+    for id in &cancelled {
+        println!("[Command] Cancelling launch {}", id);
+        downloads.cancel_group(id);
+    }
+    Ok(cancelled)
+}
+
+/// Games started by the launcher that are still running
+#[command]
+pub fn list_running(processes: State<'_, ProcessRegistry>) -> Vec<RunningGame> {
+    processes.list()
+}
+
+#[command]
+pub fn is_running(processes: State<'_, ProcessRegistry>, instance_id: String) -> bool {
+    processes.is_running(&instance_id)
+}
+
+/// Ask a running game to close and kill it if it hasn't exited after `timeout_secs`
+/// (10 by default). Returns false when the instance wasn't running.
+#[command]
+pub async fn stop_instance(
+    processes: State<'_, ProcessRegistry>,
+    instance_id: String,
+    timeout_secs: Option<u64>,
+) -> LauncherResult<bool> {
+    println!("[Command] stop_instance called for {}", instance_id);
+    let timeout = std::time::Duration::from_secs(timeout_secs.unwrap_or(10));
+    processes.inner().clone().stop(&instance_id, timeout).await
+}
+
+/// Output of the instance's latest game session, so the console can be rebuilt after a reload.
+/// `since` is the `lastSeq` of the previous call (or the `seq` of the last line received);
+/// only newer lines are returned. `search` is a case-insensitive substring.
+#[command]
+pub fn get_console_history(
+    console: State<'_, ConsoleHistory>,
+    instance_id: String,
+    since: Option<u64>,
+    search: Option<String>,
+    levels: Option<Vec<LogLevel>>,
+    limit: Option<usize>,
+) -> ConsolePage {
+    console.history(&instance_id, &ConsoleFilter { since, search, levels, limit })
+}
+
+/// Make sure a Microsoft token isn't already expired before it reaches the game.
+/// Stale tokens are refreshed through the stored session; without network the game is
+/// launched offline with the cached profile so single-player still works.
+async fn ensure_fresh_auth(window: &Window, auth: AuthData) -> LauncherResult<AuthData> {
+    let (access_token, uuid, username, xuid) = match &auth {
+        AuthData::Microsoft { access_token, uuid, username, xuid } => (access_token, uuid, username, xuid),
+        _ => return Ok(auth),
+    };
+
+    if access_token.is_empty() {
+        // Launches without a token have always gone through offline; keep that, but say so
+        println!("[Command] No access token for {}, launching in offline mode", username);
+        let warning = format!("No access token for {}: launching offline. Unlock the credential vault or log in again to play online.", username);
+        let _ = window.emit("launch-warning", warning);
+        return Ok(AuthData::Offline {
+            uuid: uuid.clone(),
+            username: username.clone(),
+            xuid: xuid.clone(),
+        });
+    }
+
+    if !token::is_expired(access_token) {
+        return Ok(auth);
+    }
+    println!("[Command] Access token for {} has expired, refreshing", username);
+
+    let account = accounts::find_account(uuid)?
+        .ok_or_else(|| LauncherError::Auth {
+            code: "SESSION_NOT_FOUND".into(),
+            message: format!("Session for {} has expired and no stored account can refresh it. Please log in again.", username),
+        })?;
+    let session = account.to_session();
+    let provider = providers::provider_for(&session.mode, session.auth_server.as_deref(), None)?;
+
+    match provider.refresh(&session).await {
+        Ok(result) => {
+            let account = accounts::update_from_auth(&result)?;
+            println!("[Command] Session refreshed for {}", account.username);
+            Ok(account.to_auth_data())
+        }
+        Err(e) if !services::services_reachable().await => {
+            println!("[Command] Refresh failed while offline ({}), launching in offline mode", e.message);
+            let warning = format!("No connection to Minecraft services: launching {} offline. Multiplayer will not work until you reconnect.", username);
+            let _ = window.emit("launch-warning", warning);
+            Ok(AuthData::Offline {
+                uuid: uuid.clone(),
+                username: username.clone(),
+                xuid: xuid.clone(),
+            })
+        }
+        Err(e) => Err(LauncherError::Auth {
+            code: e.code,
+            message: format!("Session for {} has expired and could not be refreshed ({}). Please log in again.", username, e.message),
+        }),
+    }
+}
+
+/// Pick the launch account: the one named in the options, then the instance default, then the active account
+fn resolve_account(requested: Option<&str>, instance_default: Option<&str>) -> LauncherResult<Account> {
+    if let Some(id) = requested {
+        return accounts::find_account(id)?.ok_or_else(|| LauncherError::Auth { code: "ACCOUNT_NOT_FOUND".into(), message: format!("Account {} not found", id) });
+    }
+
+    if let Some(id) = instance_default {
+        match accounts::find_account(id)? {
+            Some(account) => return Ok(account),
+            None => println!("[Command] Instance default account {} no longer exists, using active account", id),
+        }
+    }
+
+    accounts::find_active_account()?
+        .ok_or_else(|| LauncherError::Auth { code: "NO_ACCOUNT".into(), message: "No account available. Please log in first.".into() })
 }
 
 #[command]
@@ -1,8 +1,13 @@
 pub mod auth;
+pub mod accounts;
+pub mod profile;
 pub mod launcher;
 pub mod instances;
 pub mod modloaders;
 pub mod network;
 pub mod files;
+pub mod yggdrasil;
+pub mod vault;
+pub mod downloads;
 
 
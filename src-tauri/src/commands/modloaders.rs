@@ -1,6 +1,7 @@
 use tauri::command;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use crate::error::{LauncherError, LauncherResult};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoaderVersion {
@@ -32,15 +33,15 @@ struct QuiltLoaderObj {
 }
 
 #[command]
-pub async fn get_fabric_versions(minecraft_version: String) -> Result<Vec<LoaderVersion>, String> {
+pub async fn get_fabric_versions(minecraft_version: String) -> LauncherResult<Vec<LoaderVersion>> {
     let url = format!("https://meta.fabricmc.net/v2/versions/loader/{}", minecraft_version);
-    let response = reqwest::get(&url).await.map_err(|e| e.to_string())?;
+    let response = reqwest::get(&url).await?;
     
     if !response.status().is_success() {
         return Ok(Vec::new()); // Return empty if not found or error
     }
 
-    let versions: Vec<FabricLoaderResponse> = response.json().await.map_err(|e| e.to_string())?;
+    let versions: Vec<FabricLoaderResponse> = response.json().await?;
 
     let mut loader_versions: Vec<LoaderVersion> = versions.into_iter().map(|v| LoaderVersion {
         id: format!("fabric-{}", v.loader.version),
@@ -79,15 +80,15 @@ fn compare_versions(v1: &str, v2: &str) -> std::cmp::Ordering {
 }
 
 #[command]
-pub async fn get_quilt_versions(minecraft_version: String) -> Result<Vec<LoaderVersion>, String> {
+pub async fn get_quilt_versions(minecraft_version: String) -> LauncherResult<Vec<LoaderVersion>> {
     let url = format!("https://meta.quiltmc.org/v3/versions/loader/{}", minecraft_version);
-    let response = reqwest::get(&url).await.map_err(|e| e.to_string())?;
+    let response = reqwest::get(&url).await?;
 
     if !response.status().is_success() {
         return Ok(Vec::new());
     }
 
-    let versions: Vec<QuiltLoaderResponse> = response.json().await.map_err(|e| e.to_string())?;
+    let versions: Vec<QuiltLoaderResponse> = response.json().await?;
 
     let mut loader_versions: Vec<LoaderVersion> = versions.into_iter().map(|v| {
         let stable = !v.loader.version.contains("-beta") && !v.loader.version.contains("-alpha");
@@ -115,17 +116,17 @@ struct NeoForgeResponse {
 }
 
 #[command]
-pub async fn get_neoforge_versions(minecraft_version: String) -> Result<Vec<LoaderVersion>, String> {
+pub async fn get_neoforge_versions(minecraft_version: String) -> LauncherResult<Vec<LoaderVersion>> {
     // Special handling for 1.20.1 (uses 'forge' artifact)
     if minecraft_version == "1.20.1" {
         let url = "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged/forge";
-        let response = reqwest::get(url).await.map_err(|e| e.to_string())?;
+        let response = reqwest::get(url).await?;
         
         if !response.status().is_success() {
             return Ok(Vec::new());
         }
 
-        let data: NeoForgeResponse = response.json().await.map_err(|e| e.to_string())?;
+        let data: NeoForgeResponse = response.json().await?;
         
         let mut filtered_versions: Vec<LoaderVersion> = data.versions.into_iter()
             .filter(|v| v.starts_with("1.20.1-"))
@@ -145,13 +146,13 @@ pub async fn get_neoforge_versions(minecraft_version: String) -> Result<Vec<Load
     }
 
     let url = "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged/neoforge";
-    let response = reqwest::get(url).await.map_err(|e| e.to_string())?;
+    let response = reqwest::get(url).await?;
     
     if !response.status().is_success() {
         return Ok(Vec::new());
     }
 
-    let data: NeoForgeResponse = response.json().await.map_err(|e| e.to_string())?;
+    let data: NeoForgeResponse = response.json().await?;
     
     // NeoForge versions are like "20.4.106-beta" where "20.4" corresponds to MC "1.20.4"
     // We need to map minecraft_version (e.g. "1.20.4") to NeoForge major version (e.g. "20.4")
@@ -202,7 +203,7 @@ struct ForgeResponse {
 }
 
 #[command]
-pub async fn get_forge_versions(minecraft_version: String) -> Result<Vec<LoaderVersion>, String> {
+pub async fn get_forge_versions(minecraft_version: String) -> LauncherResult<Vec<LoaderVersion>> {
     // 1. Fetch Promos to identify recommended (stable)
     let promos_url = "https://files.minecraftforge.net/net/minecraftforge/forge/promotions_slim.json";
     let mut recommended_ver = String::new();
@@ -219,13 +220,13 @@ pub async fn get_forge_versions(minecraft_version: String) -> Result<Vec<LoaderV
 
     // 2. Fetch Maven Metadata for full list
     let metadata_url = "https://maven.minecraftforge.net/net/minecraftforge/forge/maven-metadata.xml";
-    let response = reqwest::get(metadata_url).await.map_err(|e| e.to_string())?;
+    let response = reqwest::get(metadata_url).await?;
 
     if !response.status().is_success() {
-        return Err(format!("Failed to fetch Forge metadata: {}", response.status()));
+        return Err(LauncherError::http_status(metadata_url, response.status().as_u16()));
     }
 
-    let xml_content = response.text().await.map_err(|e| e.to_string())?;
+    let xml_content = response.text().await?;
     
     let mut versions = Vec::new();
     let prefix = format!("{}-", minecraft_version); // e.g. "1.20.1-"
@@ -1,19 +1,12 @@
-use tauri::{command, Window, Emitter};
+use tauri::{command, State};
 use reqwest::Client;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Write;
-use std::path::Path;
-use futures::StreamExt;
-
-#[derive(Clone, serde::Serialize)]
-struct DownloadProgress {
-    id: Option<String>,
-    progress: f64,
-}
+use crate::error::{LauncherError, LauncherResult};
+use crate::launcher::download_manager::{DownloadManager, DownloadRequest};
+use crate::launcher::mirrors::{DownloadSettings, Mirror};
 
 #[command]
-pub async fn fetch_cors(url: String, headers: Option<HashMap<String, String>>) -> Result<String, String> {
+pub async fn fetch_cors(url: String, headers: Option<HashMap<String, String>>) -> LauncherResult<String> {
     let client = Client::new();
     let mut request = client.get(&url);
 
@@ -23,57 +16,37 @@ pub async fn fetch_cors(url: String, headers: Option<HashMap<String, String>>) -
         }
     }
 
-    let response = request.send().await.map_err(|e| e.to_string())?;
+    let response = request.send().await?;
     
     if !response.status().is_success() {
-        return Err(format!("Request failed with status: {}", response.status()));
+        return Err(LauncherError::http_status(&url, response.status().as_u16()));
     }
 
-    let text = response.text().await.map_err(|e| e.to_string())?;
+    let text = response.text().await?;
     
     Ok(text)
 }
 
 #[command]
-pub async fn download_file(window: Window, url: String, path: String, id: Option<String>) -> Result<(), String> {
-    let client = Client::new();
-    let response = client.get(&url).send().await.map_err(|e| e.to_string())?;
-    
-    if !response.status().is_success() {
-        return Err(format!("Request failed with status: {}", response.status()));
-    }
+pub async fn download_file(downloads: State<'_, DownloadManager>, url: String, path: String, id: Option<String>) -> LauncherResult<()> {
+    downloads.fetch(DownloadRequest::new(url, path).client_id(id)).await
+}
 
-    let total_size = response.content_length().unwrap_or(0);
-    let mut stream = response.bytes_stream();
-    
-    let path_obj = Path::new(&path);
-    if let Some(parent) = path_obj.parent() {
-        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-    }
+#[command]
+pub async fn get_download_settings() -> LauncherResult<DownloadSettings> {
+    Ok(DownloadSettings::load())
+}
 
-    let mut file = File::create(path).map_err(|e| e.to_string())?;
-    let mut downloaded: u64 = 0;
-    let mut last_emit = 0;
+/// Save the mirror list and retry policy; used by every download started afterwards
+#[command]
+pub async fn set_download_settings(downloads: State<'_, DownloadManager>, settings: DownloadSettings) -> LauncherResult<DownloadSettings> {
+    settings.save()?;
+    downloads.set_settings(settings.clone());
+    Ok(settings)
+}
 
-    while let Some(item) = stream.next().await {
-        let chunk = item.map_err(|e| e.to_string())?;
-        file.write_all(&chunk).map_err(|e| e.to_string())?;
-        downloaded += chunk.len() as u64;
-        
-        if total_size > 0 {
-            let progress = (downloaded as f64 / total_size as f64) * 100.0;
-            // Emit every 1% or so to avoid flooding
-            if progress as u64 > last_emit {
-                let payload = DownloadProgress { id: id.clone(), progress };
-                window.emit("download-progress", payload).map_err(|e| e.to_string())?;
-                last_emit = progress as u64;
-            }
-        }
-    }
-    
-    // Ensure 100% is emitted
-    let payload = DownloadProgress { id: id.clone(), progress: 100.0 };
-    window.emit("download-progress", payload).map_err(|e| e.to_string())?;
-    
-    Ok(())
+/// Ready-made mirrors the settings screen can offer
+#[command]
+pub async fn get_mirror_presets() -> LauncherResult<Vec<Mirror>> {
+    Ok(vec![Mirror::bmclapi()])
 }
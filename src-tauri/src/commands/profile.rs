@@ -0,0 +1,70 @@
+use tauri::command;
+use std::path::PathBuf;
+use crate::auth::services::{MinecraftProfile, MinecraftServicesClient, SkinVariant};
+use crate::auth::AuthError;
+use crate::commands::accounts;
+use crate::error::LauncherResult;
+
+/// Services client for a stored Microsoft account.
+/// `api_base` overrides the Minecraft services URL (e.g. a local stand-in server).
+fn services_client(uuid: &str, api_base: Option<&str>) -> LauncherResult<MinecraftServicesClient> {
+    let account = accounts::find_account(uuid)?
+        .ok_or(AuthError::new("ACCOUNT_NOT_FOUND", format!("No stored account for {}", uuid)))?;
+
+    if account.mode != "microsoft" {
+        return Err(AuthError::new("NOT_MICROSOFT", "Skins and capes can only be managed for Microsoft accounts").into());
+    }
+
+    let token = account.access_token
+        .filter(|t| !t.is_empty())
+        .ok_or(AuthError::new("MC_TOKEN_MISSING", "The account has no Minecraft access token"))?;
+
+    Ok(MinecraftServicesClient::new(api_base, &token))
+}
+
+/// `variant` comes from the frontend as "classic" or "slim"
+fn parse_variant(variant: &str) -> Result<SkinVariant, AuthError> {
+    serde_json::from_value(serde_json::Value::String(variant.to_ascii_lowercase()))
+        .map_err(|_| AuthError::new("INVALID_VARIANT", format!("Unknown skin variant '{}', expected classic or slim", variant)))
+}
+
+/// Profile with the account's skins and capes
+#[command]
+pub async fn get_minecraft_profile(uuid: String, api_base: Option<String>) -> LauncherResult<MinecraftProfile> {
+    Ok(services_client(&uuid, api_base.as_deref())?.get_profile().await?)
+}
+
+#[command]
+pub async fn upload_skin(uuid: String, path: String, variant: String, api_base: Option<String>) -> LauncherResult<MinecraftProfile> {
+    let variant = parse_variant(&variant)?;
+    println!("[Profile] Uploading {} skin for {}", variant.as_str(), uuid);
+    Ok(services_client(&uuid, api_base.as_deref())?.upload_skin(&PathBuf::from(path), variant).await?)
+}
+
+#[command]
+pub async fn reset_skin(uuid: String, api_base: Option<String>) -> LauncherResult<MinecraftProfile> {
+    Ok(services_client(&uuid, api_base.as_deref())?.reset_skin().await?)
+}
+
+#[command]
+pub async fn show_cape(uuid: String, cape_id: String, api_base: Option<String>) -> LauncherResult<MinecraftProfile> {
+    Ok(services_client(&uuid, api_base.as_deref())?.show_cape(&cape_id).await?)
+}
+
+#[command]
+pub async fn hide_cape(uuid: String, api_base: Option<String>) -> LauncherResult<MinecraftProfile> {
+    Ok(services_client(&uuid, api_base.as_deref())?.hide_cape().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variant_is_validated() {
+        assert_eq!(parse_variant("slim").unwrap(), SkinVariant::Slim);
+        assert_eq!(parse_variant("Classic").unwrap(), SkinVariant::Classic);
+        assert_eq!(parse_variant("alex").unwrap_err().code, "INVALID_VARIANT");
+        assert_eq!(parse_variant("").unwrap_err().code, "INVALID_VARIANT");
+    }
+}
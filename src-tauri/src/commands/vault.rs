@@ -0,0 +1,36 @@
+use tauri::command;
+use crate::auth::vault::{self, VaultStatus};
+use crate::error::LauncherResult;
+
+#[command]
+pub async fn get_vault_status() -> LauncherResult<VaultStatus> {
+    Ok(vault::status())
+}
+
+/// Unlock the credential vault (creating it if needed).
+/// Without a passphrase the key is kept in the OS keyring.
+#[command]
+pub async fn unlock_vault(passphrase: Option<String>) -> LauncherResult<VaultStatus> {
+    vault::unlock(passphrase.as_deref())?;
+    Ok(vault::status())
+}
+
+#[command]
+pub async fn lock_vault() -> LauncherResult<VaultStatus> {
+    vault::lock();
+    Ok(vault::status())
+}
+
+/// Re-encrypt the vault under a new passphrase, or a new keyring key when none is given
+#[command]
+pub async fn rotate_vault_key(passphrase: Option<String>) -> LauncherResult<VaultStatus> {
+    vault::rotate(passphrase.as_deref())?;
+    Ok(vault::status())
+}
+
+/// Delete every stored token; accounts stay in the list but have to log in again
+#[command]
+pub async fn wipe_vault() -> LauncherResult<VaultStatus> {
+    vault::wipe()?;
+    Ok(vault::status())
+}
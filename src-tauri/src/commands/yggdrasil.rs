@@ -0,0 +1,27 @@
+use tauri::{command, State};
+use std::path::PathBuf;
+use crate::launcher::yggdrasil_server::{SkinLibrary, YggdrasilState};
+use crate::error::LauncherResult;
+
+/// Store a skin for an offline account in the local skin library
+#[command]
+pub async fn set_offline_skin(uuid: String, path: String, slim: bool) -> LauncherResult<()> {
+    SkinLibrary::open_default().set_skin(&uuid, &PathBuf::from(path), slim)
+}
+
+#[command]
+pub async fn set_offline_cape(uuid: String, path: String) -> LauncherResult<()> {
+    SkinLibrary::open_default().set_cape(&uuid, &PathBuf::from(path))
+}
+
+#[command]
+pub async fn clear_offline_skin(uuid: String) -> LauncherResult<()> {
+    SkinLibrary::open_default().clear(&uuid)
+}
+
+/// Start the embedded Yggdrasil server if needed and return its API root
+#[command]
+pub async fn get_local_yggdrasil_url(yggdrasil: State<'_, YggdrasilState>) -> LauncherResult<String> {
+    let server = yggdrasil.get_or_start().await?;
+    Ok(server.api_root().to_string())
+}
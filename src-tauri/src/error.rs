@@ -0,0 +1,175 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::path::{Path, PathBuf};
+use crate::auth::AuthError;
+
+pub type LauncherResult<T> = Result<T, LauncherError>;
+
+/// Error type shared by the launcher and its commands.
+/// Sent to the frontend as `{ code, message, context }` so the UI can react to the `code`.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum LauncherError {
+    #[error("{message}")]
+    Network { message: String, url: Option<String>, status: Option<u16> },
+
+    #[error("{message}")]
+    Io { message: String, path: Option<PathBuf> },
+
+    #[error("Checksum mismatch for {}: expected {expected}, got {actual}", path.display())]
+    Checksum { path: PathBuf, expected: String, actual: String },
+
+    #[error("{message}")]
+    Java { message: String },
+
+    #[error("{message}")]
+    LoaderInstall { loader: String, message: String },
+
+    #[error("{message}")]
+    Auth { code: String, message: String },
+
+    #[error("{message}")]
+    Instance { instance: Option<String>, message: String },
+
+    #[error("{message}")]
+    Parse { message: String },
+
+    /// `reason` tells the UI what to ask for: `KEYRING_UNAVAILABLE` (set a passphrase),
+    /// `VAULT_LOCKED` (enter the passphrase) or `PASSPHRASE_NOT_USED`
+    #[error("{message}")]
+    Vault { reason: Option<String>, message: String },
+
+    /// The user cancelled the operation
+    #[error("{message}")]
+    Cancelled { message: String },
+
+    #[error("{0}")]
+    Other(String),
+}
+
+impl LauncherError {
+    /// Stable identifier for the frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Network { .. } => "NETWORK_ERROR",
+            Self::Io { .. } => "IO_ERROR",
+            Self::Checksum { .. } => "CHECKSUM_MISMATCH",
+            Self::Java { .. } => "JAVA_ERROR",
+            Self::LoaderInstall { .. } => "LOADER_INSTALL_FAILED",
+            Self::Auth { .. } => "AUTH_ERROR",
+            Self::Instance { .. } => "INSTANCE_ERROR",
+            Self::Parse { .. } => "PARSE_ERROR",
+            Self::Vault { .. } => "VAULT_ERROR",
+            Self::Cancelled { .. } => "CANCELLED",
+            Self::Other(_) => "LAUNCHER_ERROR",
+        }
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::Network { message: message.into(), url: None, status: None }
+    }
+
+    /// A request that reached the server but came back with an error status
+    pub fn http_status(url: &str, status: u16) -> Self {
+        Self::Network { message: format!("Request to {} failed with status {}", url, status), url: Some(url.to_string()), status: Some(status) }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::Io { message: message.into(), path: None }
+    }
+
+    /// IO failure on a specific file or directory
+    pub fn io_at(path: &Path, message: impl Into<String>) -> Self {
+        Self::Io { message: message.into(), path: Some(path.to_path_buf()) }
+    }
+
+    pub fn java(message: impl Into<String>) -> Self {
+        Self::Java { message: message.into() }
+    }
+
+    pub fn loader(loader: &str, message: impl Into<String>) -> Self {
+        Self::LoaderInstall { loader: loader.to_string(), message: message.into() }
+    }
+
+    pub fn instance(instance: Option<&str>, message: impl Into<String>) -> Self {
+        Self::Instance { instance: instance.map(str::to_string), message: message.into() }
+    }
+
+    pub fn parse(message: impl Into<String>) -> Self {
+        Self::Parse { message: message.into() }
+    }
+
+    pub fn vault(message: impl Into<String>) -> Self {
+        Self::Vault { reason: None, message: message.into() }
+    }
+
+    /// Vault error the user can resolve, see `Vault`
+    pub fn vault_reason(reason: &str, message: impl Into<String>) -> Self {
+        Self::Vault { reason: Some(reason.to_string()), message: message.into() }
+    }
+
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        Self::Cancelled { message: message.into() }
+    }
+
+    fn context(&self) -> serde_json::Value {
+        match self {
+            Self::Network { url, status, .. } => serde_json::json!({ "url": url, "status": status }),
+            Self::Io { path, .. } => serde_json::json!({ "path": path }),
+            Self::Checksum { path, expected, actual } => serde_json::json!({ "path": path, "expected": expected, "actual": actual }),
+            Self::LoaderInstall { loader, .. } => serde_json::json!({ "loader": loader }),
+            Self::Auth { code, .. } => serde_json::json!({ "reason": code }),
+            Self::Vault { reason, .. } => serde_json::json!({ "reason": reason }),
+            Self::Instance { instance, .. } => serde_json::json!({ "instance": instance }),
+            _ => serde_json::json!({}),
+        }
+    }
+}
+
+impl Serialize for LauncherError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("LauncherError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("context", &self.context())?;
+        state.end()
+    }
+}
+
+impl From<std::io::Error> for LauncherError {
+    fn from(e: std::io::Error) -> Self {
+        Self::io(e.to_string())
+    }
+}
+
+impl From<reqwest::Error> for LauncherError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Network {
+            message: e.to_string(),
+            url: e.url().map(|u| u.to_string()),
+            status: e.status().map(|s| s.as_u16()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for LauncherError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::parse(e.to_string())
+    }
+}
+
+impl From<zip::result::ZipError> for LauncherError {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self::io(format!("Zip error: {}", e))
+    }
+}
+
+impl From<AuthError> for LauncherError {
+    fn from(e: AuthError) -> Self {
+        Self::Auth { code: e.code, message: e.message }
+    }
+}
+
+impl From<tauri::Error> for LauncherError {
+    fn from(e: tauri::Error) -> Self {
+        Self::Other(e.to_string())
+    }
+}
@@ -0,0 +1,263 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Window};
+use crate::launcher::game_log::{OutputStream, SessionLog};
+use crate::launcher::log4j::LogRecord;
+
+/// Lines kept per session; the oldest are dropped first
+const MAX_CONSOLE_LINES: usize = 5000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_uppercase().as_str() {
+            "TRACE" => Some(Self::Trace),
+            "DEBUG" => Some(Self::Debug),
+            "INFO" => Some(Self::Info),
+            "WARN" | "WARNING" => Some(Self::Warn),
+            "ERROR" | "SEVERE" => Some(Self::Error),
+            "FATAL" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+
+    /// Level of a plain log line such as `[12:00:00] [Render thread/INFO]: ...`
+    fn detect(text: &str) -> Option<Self> {
+        text.match_indices('/').find_map(|(index, _)| {
+            let rest = &text[index + 1..];
+            let end = rest.find(']')?;
+            Self::parse(&rest[..end])
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleLine {
+    /// Increases across all sessions, so `since` never skips lines of a new session
+    pub seq: u64,
+    /// Unix time in milliseconds
+    pub timestamp: u64,
+    pub stream: OutputStream,
+    pub level: Option<LogLevel>,
+    pub text: String,
+    /// Only for lines parsed from log4j events
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub throwable: Option<String>,
+}
+
+/// Payload of the `console-line` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConsoleLineEvent<'a> {
+    instance_id: &'a str,
+    line: &'a ConsoleLine,
+}
+
+/// Answer to `get_console_history`
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolePage {
+    pub lines: Vec<ConsoleLine>,
+    /// Pass as `since` to only get newer lines next time
+    pub last_seq: u64,
+    /// Lines after `since` were already dropped from the buffer
+    pub truncated: bool,
+    pub pid: Option<u32>,
+}
+
+/// Which lines `get_console_history` returns
+#[derive(Debug, Clone, Default)]
+pub struct ConsoleFilter {
+    /// Only lines with a greater sequence number
+    pub since: Option<u64>,
+    /// Case-insensitive substring
+    pub search: Option<String>,
+    pub levels: Option<Vec<LogLevel>>,
+    /// Keep only the newest `limit` matches
+    pub limit: Option<usize>,
+}
+
+#[derive(Default)]
+struct ConsoleSession {
+    pid: Option<u32>,
+    lines: VecDeque<ConsoleLine>,
+    /// Level of the last line that had one; stack traces inherit it
+    last_level: Option<LogLevel>,
+    /// Sequence number of the newest line that has been dropped
+    dropped_until: u64,
+}
+
+/// Output of the latest session of every instance, kept in Tauri state
+#[derive(Clone, Default)]
+pub struct ConsoleHistory {
+    inner: Arc<Mutex<ConsoleState>>,
+}
+
+#[derive(Default)]
+struct ConsoleState {
+    next_seq: u64,
+    sessions: HashMap<String, ConsoleSession>,
+}
+
+impl ConsoleHistory {
+    /// Forget the previous session's output when the instance is launched again
+    pub fn start_session(&self, instance_id: &str, pid: u32) {
+        let mut state = self.inner.lock().unwrap();
+        state.sessions.insert(instance_id.to_string(), ConsoleSession { pid: Some(pid), ..Default::default() });
+    }
+
+    pub fn push(&self, instance_id: &str, stream: OutputStream, text: &str) -> ConsoleLine {
+        self.insert(instance_id, stream, text, None)
+    }
+
+    pub fn push_record(&self, instance_id: &str, record: &LogRecord) -> ConsoleLine {
+        self.insert(instance_id, OutputStream::Stdout, &record.to_text(), Some(record))
+    }
+
+    fn insert(&self, instance_id: &str, stream: OutputStream, text: &str, record: Option<&LogRecord>) -> ConsoleLine {
+        let mut state = self.inner.lock().unwrap();
+        state.next_seq += 1;
+        let seq = state.next_seq;
+        let session = state.sessions.entry(instance_id.to_string()).or_default();
+
+        let level = record.and_then(|record| record.level).or_else(|| LogLevel::detect(text)).or_else(|| {
+            let continuation = text.starts_with(char::is_whitespace) || text.starts_with("Caused by") || text.starts_with("Exception in thread");
+            if continuation {
+                session.last_level
+            } else if stream == OutputStream::Stderr {
+                Some(LogLevel::Error)
+            } else {
+                None
+            }
+        });
+        if level.is_some() {
+            session.last_level = level;
+        }
+
+        let line = ConsoleLine {
+            seq,
+            timestamp: record.map(|record| record.timestamp).filter(|t| *t > 0).unwrap_or_else(|| {
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or(0)
+            }),
+            stream,
+            level,
+            text: text.to_string(),
+            thread: record.map(|record| record.thread.clone()),
+            logger: record.map(|record| record.logger.clone()),
+            throwable: record.and_then(|record| record.throwable.clone()),
+        };
+
+        session.lines.push_back(line.clone());
+        while session.lines.len() > MAX_CONSOLE_LINES {
+            if let Some(dropped) = session.lines.pop_front() {
+                session.dropped_until = dropped.seq;
+            }
+        }
+        line
+    }
+
+    pub fn history(&self, instance_id: &str, filter: &ConsoleFilter) -> ConsolePage {
+        let state = self.inner.lock().unwrap();
+        let session = match state.sessions.get(instance_id) {
+            Some(session) => session,
+            None => return ConsolePage { last_seq: state.next_seq, ..Default::default() },
+        };
+
+        let since = filter.since.unwrap_or(0);
+        let search = filter.search.as_deref()
+            .filter(|search| !search.is_empty())
+            .map(str::to_lowercase);
+
+        let mut lines: Vec<ConsoleLine> = session.lines.iter()
+            .filter(|line| line.seq > since)
+            .filter(|line| match &filter.levels {
+                Some(levels) => line.level.is_some_and(|level| levels.contains(&level)),
+                None => true,
+            })
+            .filter(|line| search.as_ref().is_none_or(|search| line.text.to_lowercase().contains(search)))
+            .cloned()
+            .collect();
+        if let Some(limit) = filter.limit {
+            let excess = lines.len().saturating_sub(limit);
+            lines.drain(..excess);
+        }
+
+        ConsolePage {
+            lines,
+            last_seq: state.next_seq,
+            truncated: filter.since.is_some() && session.dropped_until > since,
+            pid: session.pid,
+        }
+    }
+}
+
+/// Sends every line the game prints to the session log, the console history and the frontend
+#[derive(Clone)]
+pub struct GameOutput {
+    instance_id: String,
+    log: SessionLog,
+    console: ConsoleHistory,
+    window: Option<Window>,
+}
+
+impl GameOutput {
+    pub fn new(instance_id: String, log: SessionLog, console: ConsoleHistory, window: Option<Window>) -> Self {
+        Self { instance_id, log, console, window }
+    }
+
+    pub fn log(&self) -> &SessionLog {
+        &self.log
+    }
+
+    pub fn line(&self, stream: OutputStream, text: &str) {
+        let line = self.console.push(&self.instance_id, stream, text);
+        self.publish(&line);
+    }
+
+    /// A structured event from the game's log4j output
+    pub fn record(&self, record: &LogRecord) {
+        let line = self.console.push_record(&self.instance_id, record);
+        self.publish(&line);
+    }
+
+    fn publish(&self, line: &ConsoleLine) {
+        // Multi-line records (stack traces) stay one console entry but keep the plain event line based
+        for text in line.text.lines() {
+            self.log.write(line.stream, text);
+            if let Some(window) = &self.window {
+                let output = match line.stream {
+                    OutputStream::Stdout => text.to_string(),
+                    OutputStream::Stderr => format!("[STDERR] {}", text),
+                };
+                let _ = window.emit("game-output", output);
+            }
+            match line.stream {
+                OutputStream::Stdout => println!("[Game] {}", text),
+                OutputStream::Stderr => println!("[Game/STDERR] {}", text),
+            }
+        }
+
+        if let Some(window) = &self.window {
+            let _ = window.emit("console-line", ConsoleLineEvent { instance_id: &self.instance_id, line });
+        }
+    }
+}
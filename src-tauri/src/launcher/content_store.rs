@@ -0,0 +1,222 @@
+use serde::Serialize;
+use std::path::Path;
+use crate::error::{LauncherError, LauncherResult};
+use crate::launcher::integrity;
+
+/// Folders that belong in the shared content root rather than in an instance's game directory
+pub const SHARED_FOLDERS: [&str; 4] = ["versions", "libraries", "assets", "natives"];
+
+/// Outcome of moving an instance's downloaded content into the shared store
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationReport {
+    /// Files the shared store did not have yet
+    pub moved: u64,
+    /// Copies of files the shared store already had, now deleted
+    pub deduplicated: u64,
+    pub freed_bytes: u64,
+    /// Files that differ from the shared copy; they are left where they are
+    pub conflicts: Vec<String>,
+}
+
+/// Sent as `content-migration-progress`; hashing a large store takes a while
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationProgress {
+    pub game_dir: String,
+    pub processed: u64,
+    pub total: u64,
+}
+
+/// Files handled between two progress reports
+const PROGRESS_STEP: u64 = 200;
+
+struct Migration<'a> {
+    report: MigrationReport,
+    progress: MigrationProgress,
+    on_progress: &'a mut dyn FnMut(&MigrationProgress),
+}
+
+impl Migration<'_> {
+    fn file_done(&mut self) {
+        self.progress.processed += 1;
+        if self.progress.processed.is_multiple_of(PROGRESS_STEP) || self.progress.processed == self.progress.total {
+            (self.on_progress)(&self.progress);
+        }
+    }
+}
+
+impl MigrationReport {
+    pub fn merge(&mut self, other: MigrationReport) {
+        self.moved += other.moved;
+        self.deduplicated += other.deduplicated;
+        self.freed_bytes += other.freed_bytes;
+        self.conflicts.extend(other.conflicts);
+    }
+}
+
+/// Move the versions, libraries, assets and native jars found in `game_dir` into `content_dir`.
+/// `on_progress` is called every few hundred files.
+pub fn migrate_game_dir(game_dir: &Path, content_dir: &Path, on_progress: &mut dyn FnMut(&MigrationProgress)) -> LauncherResult<MigrationReport> {
+    if same_dir(game_dir, content_dir) {
+        return Ok(MigrationReport::default());
+    }
+
+    let folders: Vec<&str> = SHARED_FOLDERS.into_iter().filter(|folder| game_dir.join(folder).is_dir()).collect();
+    if folders.is_empty() {
+        return Ok(MigrationReport::default());
+    }
+    let total = folders.iter().map(|folder| count_files(&game_dir.join(folder), Path::new(folder))).sum();
+    println!("[ContentStore] Migrating {} files from {:?}", total, game_dir);
+
+    let mut migration = Migration {
+        report: MigrationReport::default(),
+        progress: MigrationProgress { game_dir: game_dir.to_string_lossy().to_string(), processed: 0, total },
+        on_progress,
+    };
+    (migration.on_progress)(&migration.progress);
+
+    for folder in &folders {
+        let source = game_dir.join(folder);
+        println!("[ContentStore] Migrating {:?} into {:?}", source, content_dir.join(folder));
+        merge_dir(&source, &content_dir.join(folder), Path::new(folder), &mut migration)?;
+
+        if *folder == "versions" {
+            // Extracted natives are rebuilt on every launch, there is nothing to keep.
+            // Only dropped once the version itself made it into the store.
+            if let Ok(entries) = std::fs::read_dir(&source) {
+                for entry in entries.flatten() {
+                    let _ = std::fs::remove_dir_all(entry.path().join("natives"));
+                    let _ = std::fs::remove_dir(entry.path());
+                }
+            }
+            let _ = std::fs::remove_dir(&source);
+        }
+    }
+
+    let report = migration.report;
+    if report.moved + report.deduplicated > 0 {
+        println!("[ContentStore] {:?}: {} moved, {} duplicates removed ({} MB freed), {} conflicts",
+            game_dir, report.moved, report.deduplicated, report.freed_bytes / (1024 * 1024), report.conflicts.len());
+    }
+    Ok(report)
+}
+
+fn merge_dir(source: &Path, target: &Path, relative: &Path, migration: &mut Migration) -> LauncherResult<()> {
+    let entries = std::fs::read_dir(source)
+        .map_err(|e| LauncherError::io_at(source, format!("Failed to read directory: {}", e)))?;
+    std::fs::create_dir_all(target)
+        .map_err(|e| LauncherError::io_at(target, format!("Failed to create directory: {}", e)))?;
+
+    for entry in entries.flatten() {
+        let from = entry.path();
+        let to = target.join(entry.file_name());
+        let relative = relative.join(entry.file_name());
+
+        if from.is_dir() {
+            if !is_extracted_natives(&relative) {
+                merge_dir(&from, &to, &relative, migration)?;
+            }
+            continue;
+        }
+
+        let report = &mut migration.report;
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        if !to.exists() {
+            move_file(&from, &to)?;
+            report.moved += 1;
+        } else if is_same_file(&from, &to, size) {
+            std::fs::remove_file(&from)
+                .map_err(|e| LauncherError::io_at(&from, format!("Failed to remove duplicate: {}", e)))?;
+            report.deduplicated += 1;
+            report.freed_bytes += size;
+        } else {
+            report.conflicts.push(relative.to_string_lossy().to_string());
+        }
+        migration.file_done();
+    }
+
+    // Only succeeds once everything inside has been moved out
+    let _ = std::fs::remove_dir(source);
+    Ok(())
+}
+
+/// `versions/<id>/natives`, which is cleaned up instead of merged
+fn is_extracted_natives(relative: &Path) -> bool {
+    let parts: Vec<_> = relative.components().collect();
+    parts.len() == 3 && parts[0].as_os_str() == "versions" && parts[2].as_os_str() == "natives"
+}
+
+fn count_files(dir: &Path, relative: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else { return 0 };
+    entries.flatten()
+        .map(|entry| {
+            let relative = relative.join(entry.file_name());
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() && !is_extracted_natives(&relative) => count_files(&entry.path(), &relative),
+                Ok(kind) if kind.is_dir() => 0,
+                _ => 1,
+            }
+        })
+        .sum()
+}
+
+fn move_file(from: &Path, to: &Path) -> LauncherResult<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    // Different filesystems: copy, then drop the original
+    std::fs::copy(from, to)
+        .map_err(|e| LauncherError::io_at(to, format!("Failed to move file into the shared store: {}", e)))?;
+    std::fs::remove_file(from)
+        .map_err(|e| LauncherError::io_at(from, format!("Failed to remove moved file: {}", e)))
+}
+
+fn is_same_file(a: &Path, b: &Path, size: u64) -> bool {
+    if std::fs::metadata(b).map(|m| m.len()).ok() != Some(size) {
+        return false;
+    }
+    match (integrity::sha1_file(a), integrity::sha1_file(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn natives_are_dropped_after_the_version_is_merged() {
+        let root = std::env::temp_dir().join(format!("porcos-migrate-{}", uuid::Uuid::new_v4()));
+        let (game_dir, content_dir) = (root.join("instance"), root.join("store"));
+        write(&game_dir.join("versions/1.20.1/1.20.1.jar"), "client");
+        write(&game_dir.join("versions/1.20.1/natives/lwjgl.so"), "native");
+        write(&game_dir.join("libraries/a/a.jar"), "lib");
+        write(&content_dir.join("libraries/a/a.jar"), "lib");
+
+        let mut reports = Vec::new();
+        let report = migrate_game_dir(&game_dir, &content_dir, &mut |progress| reports.push(progress.processed)).unwrap();
+
+        assert_eq!((report.moved, report.deduplicated), (1, 1));
+        assert!(content_dir.join("versions/1.20.1/1.20.1.jar").is_file());
+        assert!(!content_dir.join("versions/1.20.1/natives").exists());
+        assert!(!game_dir.join("versions").exists());
+        // Start and end, natives are not counted
+        assert_eq!(reports, vec![0, 2]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
+
@@ -0,0 +1,633 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+use crate::error::{LauncherError, LauncherResult};
+use crate::launcher::downloader::{self, Downloader};
+use crate::launcher::integrity;
+use crate::launcher::mirrors::DownloadSettings;
+
+/// Minimum time between two aggregated progress events
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    /// Files a launch is waiting for
+    High,
+}
+
+#[derive(Debug, Clone)]
+pub struct DownloadRequest {
+    /// Tried in order; every one of them also goes through the configured mirrors
+    pub urls: Vec<String>,
+    pub path: PathBuf,
+    pub sha1: Option<String>,
+    pub size: Option<u64>,
+    pub priority: Priority,
+    /// Frontend id; when set the job also reports `download-progress` events of its own
+    pub client_id: Option<String>,
+    /// Owner of the job (a launch id), so everything it queued can be cancelled together
+    pub group: Option<String>,
+}
+
+impl DownloadRequest {
+    pub fn new(url: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            urls: vec![url.into()],
+            path: path.into(),
+            sha1: None,
+            size: None,
+            priority: Priority::Normal,
+            client_id: None,
+            group: None,
+        }
+    }
+
+    /// Extra URLs to try when the first one fails
+    pub fn fallbacks(mut self, urls: impl IntoIterator<Item = String>) -> Self {
+        self.urls.extend(urls);
+        self
+    }
+
+    pub fn checksum(mut self, sha1: Option<String>, size: Option<u64>) -> Self {
+        self.sha1 = sha1;
+        self.size = size;
+        self
+    }
+
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    pub fn client_id(mut self, id: Option<String>) -> Self {
+        self.client_id = id;
+        self
+    }
+
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Take over what another request for the same file knows: its checksum, size and extra URLs.
+    /// Fails when both expect different contents, since one of them would get the wrong file.
+    fn absorb(&mut self, other: &DownloadRequest) -> LauncherResult<()> {
+        if let (Some(ours), Some(theirs)) = (&self.sha1, &other.sha1) {
+            if !ours.eq_ignore_ascii_case(theirs) {
+                return Err(LauncherError::io_at(&self.path, format!(
+                    "{} is already being downloaded with a different checksum ({} instead of {})",
+                    self.path.display(), ours, theirs
+                )));
+            }
+        }
+        if let (Some(ours), Some(theirs)) = (self.size, other.size) {
+            if ours != theirs {
+                return Err(LauncherError::io_at(&self.path, format!(
+                    "{} is already being downloaded with a different size ({} instead of {} bytes)",
+                    self.path.display(), ours, theirs
+                )));
+            }
+        }
+
+        self.sha1 = self.sha1.take().or_else(|| other.sha1.clone());
+        self.size = self.size.or(other.size);
+        for url in &other.urls {
+            if !self.urls.contains(url) {
+                self.urls.push(url.clone());
+            }
+        }
+        if self.client_id.is_none() {
+            self.client_id = other.client_id.clone();
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobState {
+    Queued,
+    Running,
+    Paused,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadJob {
+    pub id: u64,
+    pub url: String,
+    pub path: PathBuf,
+    pub priority: Priority,
+    pub state: JobState,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// Sent as `download-manager-progress`. Counters cover everything queued since the manager was last idle.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub queued: usize,
+    pub running: usize,
+    pub paused: usize,
+    pub completed: u64,
+    pub failed: u64,
+    pub cancelled: u64,
+    pub total_jobs: u64,
+    pub downloaded_bytes: u64,
+    pub total_bytes: u64,
+    pub paused_all: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct ClientProgress {
+    id: Option<String>,
+    progress: f64,
+}
+
+struct Job {
+    info: DownloadJob,
+    request: DownloadRequest,
+    waiters: Vec<oneshot::Sender<LauncherResult<()>>>,
+    task: Option<JoinHandle<()>>,
+    /// Bumped every time the job starts, so a stopped task can't report for a newer run
+    generation: u64,
+    last_client_percent: u64,
+}
+
+#[derive(PartialEq, Eq)]
+struct QueueEntry {
+    priority: Priority,
+    seq: u64,
+    id: u64,
+}
+
+impl Ord for QueueEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Highest priority first, then first come first served
+        self.priority.cmp(&other.priority).then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+impl PartialOrd for QueueEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[derive(Default)]
+struct State {
+    jobs: HashMap<u64, Job>,
+    /// Target path -> job, so two requests for the same file share one download
+    by_path: HashMap<PathBuf, u64>,
+    queue: BinaryHeap<QueueEntry>,
+    next_id: u64,
+    next_seq: u64,
+    running: usize,
+    paused_all: bool,
+    completed: u64,
+    failed: u64,
+    cancelled: u64,
+    total_jobs: u64,
+    completed_bytes: u64,
+    last_emit: Option<Instant>,
+}
+
+impl State {
+    fn enqueue(&mut self, id: u64, priority: Priority) {
+        self.next_seq += 1;
+        self.queue.push(QueueEntry { priority, seq: self.next_seq, id });
+    }
+
+    fn progress(&self) -> DownloadProgress {
+        let count = |state: JobState| self.jobs.values().filter(|job| job.info.state == state).count();
+        let active_bytes: u64 = self.jobs.values().map(|job| job.info.downloaded).sum();
+        let active_total: u64 = self.jobs.values()
+            .map(|job| job.info.total.or(job.request.size).unwrap_or(0))
+            .sum();
+
+        DownloadProgress {
+            queued: count(JobState::Queued),
+            running: count(JobState::Running),
+            paused: count(JobState::Paused),
+            completed: self.completed,
+            failed: self.failed,
+            cancelled: self.cancelled,
+            total_jobs: self.total_jobs,
+            downloaded_bytes: self.completed_bytes + active_bytes,
+            total_bytes: self.completed_bytes + active_total,
+            paused_all: self.paused_all,
+        }
+    }
+
+    /// Stop a running job's task without touching its .part file
+    fn stop(&mut self, id: u64) {
+        if let Some(job) = self.jobs.get_mut(&id) {
+            if job.info.state == JobState::Running {
+                if let Some(task) = job.task.take() {
+                    task.abort();
+                }
+                self.running = self.running.saturating_sub(1);
+            }
+        }
+    }
+
+    fn remove(&mut self, id: u64) -> Option<Job> {
+        let job = self.jobs.remove(&id)?;
+        self.by_path.remove(&job.request.path);
+        Some(job)
+    }
+
+    /// Start a new batch once everything has finished
+    fn reset_if_idle(&mut self) {
+        if self.jobs.is_empty() {
+            self.completed = 0;
+            self.failed = 0;
+            self.cancelled = 0;
+            self.total_jobs = 0;
+            self.completed_bytes = 0;
+        }
+    }
+}
+
+struct Inner {
+    state: Mutex<State>,
+    downloader: Mutex<Downloader>,
+    app: OnceLock<AppHandle>,
+}
+
+/// Every file download in the launcher goes through this queue. It is kept in Tauri
+/// state so the frontend can pause, resume and cancel jobs, and it enforces one
+/// global concurrency limit.
+#[derive(Clone)]
+pub struct DownloadManager {
+    inner: Arc<Inner>,
+}
+
+impl Default for DownloadManager {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(State::default()),
+                downloader: Mutex::new(Downloader::new()),
+                app: OnceLock::new(),
+            }),
+        }
+    }
+}
+
+impl DownloadManager {
+    /// Events are only emitted once the manager knows the app
+    pub fn attach(&self, app: AppHandle) {
+        let _ = self.inner.app.set(app);
+    }
+
+    /// How many downloads run at once; also a sensible bound for work around them (hash checks)
+    pub fn max_concurrent(&self) -> usize {
+        self.inner.downloader.lock().unwrap().settings().max_concurrent_downloads.max(1)
+    }
+
+    /// Apply new mirror, retry and concurrency settings to downloads started from now on
+    pub fn set_settings(&self, settings: DownloadSettings) {
+        *self.inner.downloader.lock().unwrap() = Downloader::with_settings(settings);
+        self.pump();
+    }
+
+    /// Queue a download and wait for it. A request for a file that is already
+    /// queued joins that job (raising its priority if needed) instead of downloading twice;
+    /// its checksum and fallback URLs are merged into the job.
+    pub async fn fetch(&self, request: DownloadRequest) -> LauncherResult<()> {
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            match state.by_path.get(&request.path).copied() {
+                Some(id) => {
+                    let job = state.jobs.get_mut(&id).expect("indexed job exists");
+                    job.request.absorb(&request)?;
+                    job.info.total = job.info.total.or(request.size);
+                    job.waiters.push(sender);
+                    if request.priority > job.info.priority {
+                        job.info.priority = request.priority;
+                        job.request.priority = request.priority;
+                        if job.info.state == JobState::Queued {
+                            state.enqueue(id, request.priority);
+                        }
+                    }
+                }
+                None => {
+                    state.next_id += 1;
+                    state.total_jobs += 1;
+                    let id = state.next_id;
+                    let job = Job {
+                        info: DownloadJob {
+                            id,
+                            url: request.urls.first().cloned().unwrap_or_default(),
+                            path: request.path.clone(),
+                            priority: request.priority,
+                            state: JobState::Queued,
+                            downloaded: 0,
+                            total: request.size,
+                        },
+                        request,
+                        waiters: vec![sender],
+                        task: None,
+                        generation: 0,
+                        last_client_percent: 0,
+                    };
+                    state.by_path.insert(job.request.path.clone(), id);
+                    state.enqueue(id, job.info.priority);
+                    state.jobs.insert(id, job);
+                }
+            }
+        }
+
+        self.pump();
+        receiver.await.unwrap_or_else(|_| Err(LauncherError::cancelled("Download was dropped")))
+    }
+
+    pub fn jobs(&self) -> Vec<DownloadJob> {
+        let state = self.inner.state.lock().unwrap();
+        let mut jobs: Vec<DownloadJob> = state.jobs.values().map(|job| job.info.clone()).collect();
+        jobs.sort_by(|a, b| b.priority.cmp(&a.priority).then(a.id.cmp(&b.id)));
+        jobs
+    }
+
+    pub fn progress(&self) -> DownloadProgress {
+        self.inner.state.lock().unwrap().progress()
+    }
+
+    pub fn pause(&self, id: u64) -> LauncherResult<()> {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            state.stop(id);
+            let job = state.jobs.get_mut(&id).ok_or_else(|| unknown_job(id))?;
+            job.info.state = JobState::Paused;
+        }
+        self.pump();
+        Ok(())
+    }
+
+    pub fn resume(&self, id: u64) -> LauncherResult<()> {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            let job = state.jobs.get_mut(&id).ok_or_else(|| unknown_job(id))?;
+            if job.info.state != JobState::Paused {
+                return Ok(());
+            }
+            job.info.state = JobState::Queued;
+            let priority = job.info.priority;
+            state.enqueue(id, priority);
+        }
+        self.pump();
+        Ok(())
+    }
+
+    pub fn cancel(&self, id: u64) -> LauncherResult<()> {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            state.stop(id);
+            let job = state.remove(id).ok_or_else(|| unknown_job(id))?;
+            state.cancelled += 1;
+            Self::cancel_job(job);
+            state.reset_if_idle();
+        }
+        self.pump();
+        Ok(())
+    }
+
+    /// Stop every running download and hold the queue until `resume_all`
+    pub fn pause_all(&self) {
+        let mut state = self.inner.state.lock().unwrap();
+        state.paused_all = true;
+        let running: Vec<u64> = state.jobs.values()
+            .filter(|job| job.info.state == JobState::Running)
+            .map(|job| job.info.id)
+            .collect();
+        for id in running {
+            state.stop(id);
+            if let Some(job) = state.jobs.get_mut(&id) {
+                job.info.state = JobState::Queued;
+                let priority = job.info.priority;
+                state.enqueue(id, priority);
+            }
+        }
+        self.emit(&mut state, true);
+    }
+
+    pub fn resume_all(&self) {
+        self.inner.state.lock().unwrap().paused_all = false;
+        self.pump();
+    }
+
+    pub fn cancel_all(&self) {
+        self.cancel_where(|_| true);
+    }
+
+    /// Cancel every job queued by one owner, e.g. a launch that was aborted
+    pub fn cancel_group(&self, group: &str) {
+        self.cancel_where(|job| job.request.group.as_deref() == Some(group));
+    }
+
+    fn cancel_where(&self, matches: impl Fn(&Job) -> bool) {
+        let mut state = self.inner.state.lock().unwrap();
+        let ids: Vec<u64> = state.jobs.values()
+            .filter(|job| matches(job))
+            .map(|job| job.info.id)
+            .collect();
+        for id in ids {
+            state.stop(id);
+            if let Some(job) = state.remove(id) {
+                state.cancelled += 1;
+                Self::cancel_job(job);
+            }
+        }
+        state.reset_if_idle();
+        self.emit(&mut state, true);
+        drop(state);
+        self.pump();
+    }
+
+    fn cancel_job(job: Job) {
+        let _ = std::fs::remove_file(downloader::part_path(&job.request.path));
+        for waiter in job.waiters {
+            let _ = waiter.send(Err(LauncherError::cancelled(format!("Download of {} was cancelled", job.info.url))));
+        }
+    }
+
+    /// Start queued jobs until the concurrency limit is reached
+    fn pump(&self) {
+        let downloader = self.inner.downloader.lock().unwrap().clone();
+        let limit = downloader.settings().max_concurrent_downloads.max(1);
+        let mut state = self.inner.state.lock().unwrap();
+
+        while !state.paused_all && state.running < limit {
+            let Some(entry) = state.queue.pop() else { break };
+            let Some(job) = state.jobs.get_mut(&entry.id) else { continue };
+            // Stale entries are left behind by priority changes, pauses and restarts
+            if job.info.state != JobState::Queued || job.info.priority != entry.priority {
+                continue;
+            }
+
+            job.info.state = JobState::Running;
+            job.generation += 1;
+            let (generation, request) = (job.generation, job.request.clone());
+            let manager = self.clone();
+            let downloader = downloader.clone();
+            job.task = Some(tauri::async_runtime::spawn(async move {
+                manager.run(entry.id, generation, request, downloader).await;
+            }));
+            state.running += 1;
+        }
+
+        self.emit(&mut state, false);
+    }
+
+    async fn run(&self, id: u64, generation: u64, request: DownloadRequest, downloader: Downloader) {
+        let mut result = Err(LauncherError::network(format!("No URL to download {} from", request.path.display())));
+        for url in &request.urls {
+            match downloader.download_with_progress(url, &request.path, |downloaded, total| {
+                self.on_progress(id, generation, downloaded, total);
+            }).await {
+                Ok(_) => {
+                    result = Ok(());
+                    break;
+                }
+                Err(e) => {
+                    println!("[DownloadManager] {} not available from {}: {}", request.path.display(), url, e);
+                    result = Err(e);
+                }
+            }
+        }
+
+        // A request that joined while this one was running may have brought the checksum
+        let (sha1, size) = self.inner.state.lock().unwrap().jobs.get(&id)
+            .map(|job| (job.request.sha1.clone(), job.request.size))
+            .unwrap_or((request.sha1, request.size));
+        if result.is_ok() && sha1.is_some() {
+            let path = request.path.clone();
+            result = tokio::task::spawn_blocking(move || integrity::verify_download(&path, sha1.as_deref(), size))
+                .await
+                .unwrap_or_else(|e| Err(LauncherError::Other(e.to_string())));
+        }
+
+        self.finish(id, generation, result);
+    }
+
+    fn on_progress(&self, id: u64, generation: u64, downloaded: u64, total: Option<u64>) {
+        let mut state = self.inner.state.lock().unwrap();
+        let Some(job) = state.jobs.get_mut(&id) else { return };
+        if job.generation != generation {
+            return;
+        }
+        job.info.downloaded = downloaded;
+        job.info.total = total.or(job.info.total);
+
+        if let (Some(client_id), Some(total)) = (&job.request.client_id, job.info.total.filter(|t| *t > 0)) {
+            let percent = downloaded * 100 / total;
+            // Emit every 1% or so to avoid flooding
+            if percent > job.last_client_percent {
+                job.last_client_percent = percent;
+                let event = ClientProgress { id: Some(client_id.clone()), progress: downloaded as f64 / total as f64 * 100.0 };
+                if let Some(app) = self.inner.app.get() {
+                    let _ = app.emit("download-progress", event);
+                }
+            }
+        }
+
+        self.emit(&mut state, false);
+    }
+
+    fn finish(&self, id: u64, generation: u64, result: LauncherResult<()>) {
+        {
+            let mut state = self.inner.state.lock().unwrap();
+            // A paused or cancelled job has already been accounted for
+            match state.jobs.get(&id) {
+                Some(job) if job.generation == generation && job.info.state == JobState::Running => {}
+                _ => return,
+            }
+
+            let job = state.remove(id).expect("checked above");
+            state.running = state.running.saturating_sub(1);
+            match &result {
+                Ok(()) => {
+                    state.completed += 1;
+                    state.completed_bytes += job.info.downloaded;
+                    if let (Some(client_id), Some(app)) = (&job.request.client_id, self.inner.app.get()) {
+                        let _ = app.emit("download-progress", ClientProgress { id: Some(client_id.clone()), progress: 100.0 });
+                    }
+                }
+                Err(e) => {
+                    println!("[DownloadManager] Failed to download {}: {}", job.info.url, e);
+                    state.failed += 1;
+                }
+            }
+            for waiter in job.waiters {
+                let _ = waiter.send(result.clone());
+            }
+            let idle = state.jobs.is_empty();
+            self.emit(&mut state, idle);
+            state.reset_if_idle();
+        }
+        self.pump();
+    }
+
+    fn emit(&self, state: &mut State, force: bool) {
+        let Some(app) = self.inner.app.get() else { return };
+        let now = Instant::now();
+        if !force && state.last_emit.is_some_and(|last| now.duration_since(last) < PROGRESS_INTERVAL) {
+            return;
+        }
+        state.last_emit = Some(now);
+        let _ = app.emit("download-manager-progress", state.progress());
+    }
+}
+
+fn unknown_job(id: u64) -> LauncherError {
+    LauncherError::Other(format!("Download {} not found", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joining_request_adds_checksum_and_fallbacks() {
+        let mut job = DownloadRequest::new("https://a/lib.jar", "/tmp/lib.jar");
+        let joiner = DownloadRequest::new("https://a/lib.jar", "/tmp/lib.jar")
+            .fallbacks(vec!["https://b/lib.jar".to_string()])
+            .checksum(Some("ABC".to_string()), Some(3));
+
+        job.absorb(&joiner).unwrap();
+        assert_eq!(job.sha1.as_deref(), Some("ABC"));
+        assert_eq!(job.size, Some(3));
+        assert_eq!(job.urls, vec!["https://a/lib.jar", "https://b/lib.jar"]);
+
+        // Same checksum in another case is the same file
+        let same = DownloadRequest::new("https://a/lib.jar", "/tmp/lib.jar").checksum(Some("abc".to_string()), None);
+        assert!(job.absorb(&same).is_ok());
+    }
+
+    #[test]
+    fn joining_request_with_other_checksum_is_rejected() {
+        let mut job = DownloadRequest::new("https://a/lib.jar", "/tmp/lib.jar").checksum(Some("abc".to_string()), None);
+        let joiner = DownloadRequest::new("https://b/lib.jar", "/tmp/lib.jar").checksum(Some("def".to_string()), None);
+
+        let err = job.absorb(&joiner).unwrap_err();
+        assert_eq!(err.code(), "IO_ERROR");
+        assert_eq!(job.urls, vec!["https://a/lib.jar"]);
+
+        let bigger = DownloadRequest::new("https://a/lib.jar", "/tmp/lib.jar").checksum(None, Some(10));
+        job.size = Some(3);
+        assert!(job.absorb(&bigger).is_err());
+    }
+}
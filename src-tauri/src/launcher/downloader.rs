@@ -0,0 +1,255 @@
+use futures::StreamExt;
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use crate::error::{LauncherError, LauncherResult};
+use crate::launcher::mirrors::DownloadSettings;
+
+/// Time allowed to open the connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// A download is only considered stalled when no data arrives for this long,
+/// so large jars on slow connections are not cut off half way
+const CHUNK_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// First retry delay; doubled after every failed attempt
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+/// Streams files to `<name>.part`, resumes interrupted transfers with HTTP Range
+/// and only moves the file to its final path once it is complete and synced.
+/// Transient failures are retried with exponential backoff, and every URL is
+/// tried through the configured mirrors first.
+#[derive(Clone)]
+pub struct Downloader {
+    client: reqwest::Client,
+    settings: Arc<DownloadSettings>,
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Downloader {
+    pub fn new() -> Self {
+        Self::with_settings(DownloadSettings::load())
+    }
+
+    pub fn with_settings(settings: DownloadSettings) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("PorcosLauncher/1.0")
+            .connect_timeout(CONNECT_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self { client, settings: Arc::new(settings) }
+    }
+
+    pub fn settings(&self) -> &DownloadSettings {
+        &self.settings
+    }
+
+    /// Download `url` to `path`, calling `on_progress(downloaded, total)` as chunks arrive.
+    /// Returns the size of the finished file.
+    pub async fn download_with_progress<F>(&self, url: &str, path: &Path, mut on_progress: F) -> LauncherResult<u64>
+    where
+        F: FnMut(u64, Option<u64>),
+    {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| LauncherError::io_at(parent, format!("Failed to create directory: {}", e)))?;
+        }
+
+        let part_path = part_path(path);
+        let mut last_error = None;
+
+        for (index, candidate) in self.settings.candidates(url).into_iter().enumerate() {
+            // A partial file left by a paused or interrupted download is resumed, but one
+            // from another mirror is not trusted to line up with this one
+            if index > 0 {
+                let _ = std::fs::remove_file(&part_path);
+            }
+
+            for attempt in 1..=self.max_attempts() {
+                let error = match self.transfer(&candidate, &part_path, &mut on_progress).await {
+                    Ok(size) => {
+                        std::fs::rename(&part_path, path)
+                            .map_err(|e| LauncherError::io_at(path, format!("Failed to move finished download into place: {}", e)))?;
+                        return Ok(size);
+                    }
+                    // The .part file is kept, so the next attempt resumes where this one stopped
+                    Err(Transfer::Interrupted(e)) => e,
+                    Err(Transfer::Failed(e)) if is_transient(&e) => e,
+                    Err(Transfer::Failed(e)) => {
+                        last_error = Some(e);
+                        break;
+                    }
+                };
+
+                println!("[Downloader] {} failed ({}), attempt {}/{}", candidate, error, attempt, self.max_attempts());
+                last_error = Some(error);
+                if attempt < self.max_attempts() {
+                    tokio::time::sleep(backoff(attempt)).await;
+                }
+            }
+        }
+
+        let _ = std::fs::remove_file(&part_path);
+        Err(last_error.unwrap_or_else(|| LauncherError::network(format!("Failed to download {}", url))))
+    }
+
+    /// Small files (version manifests and JSONs) fetched into memory, with the same mirrors and retries
+    pub async fn fetch_bytes(&self, url: &str) -> LauncherResult<Vec<u8>> {
+        let mut last_error = None;
+
+        for candidate in self.settings.candidates(url) {
+            for attempt in 1..=self.max_attempts() {
+                let error = match self.get_bytes(&candidate).await {
+                    Ok(bytes) => return Ok(bytes),
+                    Err(e) if is_transient(&e) => e,
+                    Err(e) => {
+                        last_error = Some(e);
+                        break;
+                    }
+                };
+
+                println!("[Downloader] {} failed ({}), attempt {}/{}", candidate, error, attempt, self.max_attempts());
+                last_error = Some(error);
+                if attempt < self.max_attempts() {
+                    tokio::time::sleep(backoff(attempt)).await;
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| LauncherError::network(format!("Failed to fetch {}", url))))
+    }
+
+    fn max_attempts(&self) -> u32 {
+        self.settings.max_attempts.max(1)
+    }
+
+    async fn get_bytes(&self, url: &str) -> LauncherResult<Vec<u8>> {
+        let response = self.client.get(url)
+            .send()
+            .await
+            .map_err(|e| LauncherError::network(format!("Failed to fetch {}: {}", url, e)))?;
+
+        if !response.status().is_success() {
+            return Err(LauncherError::http_status(url, response.status().as_u16()));
+        }
+
+        let bytes = tokio::time::timeout(CHUNK_IDLE_TIMEOUT, response.bytes())
+            .await
+            .map_err(|_| LauncherError::network(format!("No data received from {} for {}s", url, CHUNK_IDLE_TIMEOUT.as_secs())))?
+            .map_err(|e| LauncherError::network(format!("Failed to read response: {}", e)))?;
+        Ok(bytes.to_vec())
+    }
+
+    /// One request, appending to whatever part of the file is already on disk
+    async fn transfer<F>(&self, url: &str, part_path: &Path, on_progress: &mut F) -> Result<u64, Transfer>
+    where
+        F: FnMut(u64, Option<u64>),
+    {
+        let existing = std::fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+
+        let mut request = self.client.get(url);
+        if existing > 0 {
+            request = request.header(RANGE, format!("bytes={}-", existing));
+        }
+        let response = request.send()
+            .await
+            .map_err(|e| Transfer::Interrupted(LauncherError::network(format!("Download failed (network): {}", e))))?;
+
+        let status = response.status();
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The partial file no longer matches what the server has; start over
+            let _ = std::fs::remove_file(part_path);
+            return Err(Transfer::Interrupted(LauncherError::http_status(url, status.as_u16())));
+        }
+        if !status.is_success() {
+            return Err(Transfer::Failed(LauncherError::http_status(url, status.as_u16())));
+        }
+
+        // A plain 200 means the server ignored the range and is sending the whole file
+        let resuming = existing > 0 && status == StatusCode::PARTIAL_CONTENT;
+        let mut downloaded = if resuming { existing } else { 0 };
+        let total = response.content_length().map(|len| len + downloaded);
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resuming)
+            .truncate(!resuming)
+            .open(part_path)
+            .await
+            .map_err(|e| Transfer::Failed(LauncherError::io_at(part_path, format!("Failed to open file: {}", e))))?;
+
+        on_progress(downloaded, total);
+        let mut stream = response.bytes_stream();
+        loop {
+            let chunk = match tokio::time::timeout(CHUNK_IDLE_TIMEOUT, stream.next()).await {
+                Ok(Some(Ok(chunk))) => chunk,
+                Ok(Some(Err(e))) => {
+                    let _ = file.flush().await;
+                    return Err(Transfer::Interrupted(LauncherError::network(format!("Failed to read response: {}", e))));
+                }
+                Ok(None) => break,
+                Err(_) => {
+                    let _ = file.flush().await;
+                    return Err(Transfer::Interrupted(LauncherError::network(format!(
+                        "No data received for {}s", CHUNK_IDLE_TIMEOUT.as_secs()
+                    ))));
+                }
+            };
+
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| Transfer::Failed(LauncherError::io_at(part_path, format!("Failed to write file: {}", e))))?;
+            downloaded += chunk.len() as u64;
+            on_progress(downloaded, total);
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| Transfer::Failed(LauncherError::io_at(part_path, format!("Failed to write file: {}", e))))?;
+        file.sync_all()
+            .await
+            .map_err(|e| Transfer::Failed(LauncherError::io_at(part_path, format!("Failed to sync file: {}", e))))?;
+
+        if let Some(total) = total {
+            if downloaded < total {
+                return Err(Transfer::Interrupted(LauncherError::network(format!(
+                    "Connection closed after {} of {} bytes", downloaded, total
+                ))));
+            }
+        }
+
+        Ok(downloaded)
+    }
+}
+
+/// Whether a failed transfer can be resumed from its .part file
+enum Transfer {
+    Interrupted(LauncherError),
+    Failed(LauncherError),
+}
+
+/// Connection problems and server-side errors are worth retrying; a 404 or 403 is not
+fn is_transient(error: &LauncherError) -> bool {
+    match error {
+        LauncherError::Network { status: None, .. } => true,
+        LauncherError::Network { status: Some(status), .. } => *status == 408 || *status == 429 || *status >= 500,
+        _ => false,
+    }
+}
+
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1))).min(MAX_BACKOFF)
+}
+
+pub(crate) fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().map(|n| n.to_os_string()).unwrap_or_default();
+    name.push(".part");
+    path.with_file_name(name)
+}
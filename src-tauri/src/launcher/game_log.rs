@@ -0,0 +1,176 @@
+use serde::Serialize;
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use crate::launcher::launch_plan::LaunchPlan;
+
+/// Session logs kept per instance; older ones are deleted when a new session starts
+const MAX_SESSION_LOGS: usize = 10;
+pub const SESSION_LOG_DIR: &str = "launcher-logs";
+
+/// Which pipe of the game process a line came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+impl OutputStream {
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Stdout => "OUT",
+            Self::Stderr => "ERR",
+        }
+    }
+}
+
+/// Everything one game session printed, written to `<game dir>/launcher-logs/<timestamp>.log`.
+/// Logging is best effort: when the file can't be created the session runs without it.
+#[derive(Clone)]
+pub struct SessionLog {
+    path: Option<PathBuf>,
+    file: Arc<Mutex<Option<LineWriter<File>>>>,
+}
+
+impl SessionLog {
+    /// Start a new log with the (redacted) launch command at the top
+    pub fn create(game_dir: &Path, instance_id: &str, plan: &LaunchPlan) -> Self {
+        let dir = game_dir.join(SESSION_LOG_DIR);
+        let opened = std::fs::create_dir_all(&dir)
+            .and_then(|_| {
+                let path = unique_path(&dir, &format_timestamp(unix_now(), "%Y-%m-%d_%H-%M-%S"));
+                File::create(&path).map(|file| (path, file))
+            });
+
+        let (path, file) = match opened {
+            Ok(opened) => opened,
+            Err(e) => {
+                println!("[SessionLog] Could not create session log in {:?}: {}", dir, e);
+                return Self { path: None, file: Arc::new(Mutex::new(None)) };
+            }
+        };
+        rotate(&dir, &path);
+
+        let log = Self { path: Some(path), file: Arc::new(Mutex::new(Some(LineWriter::new(file)))) };
+        let plan = plan.redacted();
+        log.note(&format!("PorcosLauncher session log, started {} UTC", format_timestamp(unix_now(), "%Y-%m-%d %H:%M:%S")));
+        log.note(&format!("Instance: {}", instance_id));
+        log.note(&format!("Version: {}", plan.version));
+        log.note(&format!("Working directory: {}", plan.working_dir.to_string_lossy()));
+        if !plan.env.is_empty() {
+            log.note(&format!("Environment: {}", plan.env.keys().cloned().collect::<Vec<_>>().join(", ")));
+        }
+        log.note(&format!("Command: {:?}", plan.command()));
+        log.note("----------------------------------------");
+        log
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// A line printed by the game
+    pub fn write(&self, stream: OutputStream, line: &str) {
+        self.append(&format!("[{}] [{}] {}", format_timestamp(unix_now(), "%H:%M:%S"), stream.tag(), line));
+    }
+
+    /// A message from the launcher itself (exit code, crash report location)
+    pub fn note(&self, message: &str) {
+        self.append(&format!("[{}] [LAUNCHER] {}", format_timestamp(unix_now(), "%H:%M:%S"), message));
+    }
+
+    fn append(&self, line: &str) {
+        if let Some(file) = self.file.lock().unwrap().as_mut() {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+/// Delete the oldest session logs so only the newest `MAX_SESSION_LOGS` remain
+fn rotate(dir: &Path, current: &Path) {
+    let mut logs: Vec<PathBuf> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries.flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "log") && path != current)
+            .collect(),
+        Err(_) => return,
+    };
+    // Names are timestamps, so they sort chronologically
+    logs.sort();
+
+    let excess = (logs.len() + 1).saturating_sub(MAX_SESSION_LOGS);
+    for old in logs.into_iter().take(excess) {
+        if let Err(e) = std::fs::remove_file(&old) {
+            println!("[SessionLog] Failed to delete old log {:?}: {}", old, e);
+        }
+    }
+}
+
+/// Later sessions of the same second get `_01`, `_02`, ... which sort after the first one
+fn unique_path(dir: &Path, stem: &str) -> PathBuf {
+    let mut path = dir.join(format!("{}.log", stem));
+    let mut counter = 1;
+    while path.exists() {
+        path = dir.join(format!("{}_{:02}.log", stem, counter));
+        counter += 1;
+    }
+    path
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// UTC time with `%Y %m %d %H %M %S` placeholders
+fn format_timestamp(secs: u64, pattern: &str) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    pattern
+        .replace("%Y", &format!("{:04}", year))
+        .replace("%m", &format!("{:02}", month))
+        .replace("%d", &format!("{:02}", day))
+        .replace("%H", &format!("{:02}", rem / 3_600))
+        .replace("%M", &format!("{:02}", rem % 3_600 / 60))
+        .replace("%S", &format!("{:02}", rem % 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_keeps_the_newest_logs_of_the_same_second() {
+        let dir = std::env::temp_dir().join(format!("porcos-logs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut created = Vec::new();
+        for _ in 0..MAX_SESSION_LOGS + 2 {
+            let path = unique_path(&dir, "2024-01-01_00-00-00");
+            std::fs::write(&path, "").unwrap();
+            created.push(path);
+        }
+
+        let current = created.last().unwrap();
+        rotate(&dir, current);
+        // Only the two oldest go, the current log and the nine before it stay
+        assert!(created[..2].iter().all(|path| !path.exists()));
+        assert!(created[2..].iter().all(|path| path.exists()));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
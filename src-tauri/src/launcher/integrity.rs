@@ -0,0 +1,120 @@
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::io::Read;
+use std::path::Path;
+use crate::error::{LauncherError, LauncherResult};
+
+pub fn sha1_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(bytes);
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash a file without loading it into memory
+pub fn sha1_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha1::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// SHA-256 of a file, for downloads that publish no SHA-1 (authlib-injector)
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    use crypto::digest::Digest as _;
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = crypto::sha2::Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.input(&buffer[..read]);
+    }
+    Ok(hasher.result_str())
+}
+
+/// Whether a file exists and matches the expected size and SHA-1.
+/// The size is checked first so truncated files are caught without hashing them.
+pub fn is_valid(path: &Path, sha1: Option<&str>, size: Option<u64>) -> bool {
+    let meta = match std::fs::metadata(path) {
+        Ok(meta) if meta.is_file() => meta,
+        _ => return false,
+    };
+
+    if let Some(size) = size {
+        if meta.len() != size {
+            return false;
+        }
+    }
+
+    match sha1 {
+        Some(expected) => sha1_file(path).map(|actual| actual.eq_ignore_ascii_case(expected)).unwrap_or(false),
+        None => true,
+    }
+}
+
+/// State of a file on disk compared to what the version expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileStatus {
+    Present,
+    Missing,
+    HashMismatch,
+}
+
+/// Like [`is_valid`], but tells a missing file apart from a corrupt one
+pub fn file_status(path: &Path, sha1: Option<&str>, size: Option<u64>) -> FileStatus {
+    if !path.is_file() {
+        FileStatus::Missing
+    } else if is_valid(path, sha1, size) {
+        FileStatus::Present
+    } else {
+        FileStatus::HashMismatch
+    }
+}
+
+/// Check a freshly downloaded file; a bad file is deleted so the next run downloads it again
+pub fn verify_download(path: &Path, sha1: Option<&str>, size: Option<u64>) -> LauncherResult<()> {
+    let expected = match sha1 {
+        Some(expected) => expected,
+        None => return Ok(()),
+    };
+
+    let actual = sha1_file(path)
+        .map_err(|e| LauncherError::io_at(path, format!("Failed to hash file: {}", e)))?;
+    let size_matches = size.is_none_or(|size| std::fs::metadata(path).map(|m| m.len() == size).unwrap_or(false));
+
+    if actual.eq_ignore_ascii_case(expected) && size_matches {
+        return Ok(());
+    }
+
+    let _ = std::fs::remove_file(path);
+    Err(LauncherError::Checksum { path: path.to_path_buf(), expected: expected.to_string(), actual })
+}
+
+/// Outcome of checking (and repairing) the files of an installed version
+#[derive(Debug, Default, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+    pub checked: u64,
+    /// Files that were missing or corrupt and have been downloaded again
+    pub repaired: Vec<String>,
+    /// Files that are still missing or corrupt after the repair attempt
+    pub failed: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn merge(&mut self, other: VerifyReport) {
+        self.checked += other.checked;
+        self.repaired.extend(other.repaired);
+        self.failed.extend(other.failed);
+    }
+}
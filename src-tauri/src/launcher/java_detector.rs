@@ -1,8 +1,9 @@
 // Simple Java detection - checks common locations
 use std::path::PathBuf;
 use std::process::Command;
+use crate::error::{LauncherError, LauncherResult};
 
-pub fn find_java() -> Result<PathBuf, String> {
+pub fn find_java() -> LauncherResult<PathBuf> {
     // Try to run `java -version` to see if Java is in PATH
     if let Ok(output) = Command::new("java").arg("-version").output() {
         if output.status.success() {
@@ -76,5 +77,5 @@ pub fn find_java() -> Result<PathBuf, String> {
         }
     }
 
-    Err("Java not found. Please install Java 17 or newer.".to_string())
+    Err(LauncherError::java("Java not found. Please install Java 17 or newer."))
 }
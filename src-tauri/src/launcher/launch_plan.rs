@@ -0,0 +1,165 @@
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use crate::launcher::integrity::FileStatus;
+
+/// Shown instead of access tokens in plans sent to the frontend
+const REDACTED: &str = "<redacted>";
+/// Environment variable exported scripts read the access token from
+const ACCESS_TOKEN_VAR: &str = "MC_ACCESS_TOKEN";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClasspathEntry {
+    pub path: PathBuf,
+    pub status: FileStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NativeEntry {
+    pub path: PathBuf,
+    pub status: FileStatus,
+    pub excludes: Vec<String>,
+}
+
+/// Everything needed to start the game: the resolved Java, the final arguments and
+/// the state of the files they point to
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchPlan {
+    pub version: String,
+    pub java_path: PathBuf,
+    pub working_dir: PathBuf,
+    pub jvm_args: Vec<String>,
+    pub main_class: String,
+    pub game_args: Vec<String>,
+    /// Program (and its arguments) Java is started through, empty for none
+    pub wrapper: Vec<String>,
+    /// Extra environment variables for the game process
+    pub env: BTreeMap<String, String>,
+    /// Client jar first, then the libraries in version JSON order
+    pub classpath: Vec<ClasspathEntry>,
+    pub natives_dir: PathBuf,
+    /// Native jars that are extracted into `natives_dir` before the game starts
+    pub natives: Vec<NativeEntry>,
+    /// Log4j config from the version JSON, passed to the JVM when present
+    pub logging_config: Option<ClasspathEntry>,
+    /// Steps a dry run could not resolve without side effects (loader installers, the embedded
+    /// auth server); the real launch takes care of them
+    pub pending: Vec<String>,
+    /// Values that must not leave the launcher (access tokens)
+    #[serde(skip)]
+    pub secrets: Vec<String>,
+}
+
+impl LaunchPlan {
+    /// The process to spawn for this plan
+    pub fn command(&self) -> std::process::Command {
+        let mut command = match self.wrapper.split_first() {
+            Some((program, wrapper_args)) => {
+                let mut command = std::process::Command::new(program);
+                command.args(wrapper_args).arg(&self.java_path);
+                command
+            }
+            None => std::process::Command::new(&self.java_path),
+        };
+        command
+            .envs(&self.env)
+            .args(&self.jvm_args)
+            .arg(&self.main_class)
+            .args(&self.game_args)
+            .current_dir(&self.working_dir);
+        command
+    }
+
+    /// Copy of the plan with every secret replaced, safe to show or log
+    pub fn redacted(&self) -> Self {
+        let redact = |args: &[String]| -> Vec<String> {
+            args.iter().map(|arg| self.replace_secrets(arg, REDACTED)).collect()
+        };
+        Self {
+            jvm_args: redact(&self.jvm_args),
+            game_args: redact(&self.game_args),
+            secrets: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    fn replace_secrets(&self, arg: &str, replacement: &str) -> String {
+        self.secrets.iter()
+            .filter(|secret| !secret.is_empty())
+            .fold(arg.to_string(), |arg, secret| arg.replace(secret.as_str(), replacement))
+    }
+
+    /// Script that starts the game by hand: a batch file on Windows, a POSIX shell script elsewhere.
+    /// The access token is read from `MC_ACCESS_TOKEN` instead of being written to disk.
+    pub fn shell_script(&self) -> String {
+        let args: Vec<&String> = self.jvm_args.iter()
+            .chain(std::iter::once(&self.main_class))
+            .chain(self.game_args.iter())
+            .collect();
+
+        if cfg!(windows) {
+            let mut script = String::from("@echo off\r\n");
+            script.push_str(&format!("rem Minecraft {} - generated by PorcosLauncher\r\n", self.version));
+            script.push_str(&format!("rem Set {} before running to play online\r\n", ACCESS_TOKEN_VAR));
+            script.push_str(&format!("cd /d {}\r\n", self.batch_quote(&self.working_dir.to_string_lossy())));
+            for (key, value) in &self.env {
+                script.push_str(&format!("set {}\r\n", self.batch_quote(&format!("{}={}", key, value))));
+            }
+            for arg in &self.wrapper {
+                script.push_str(&self.batch_quote(arg));
+                script.push(' ');
+            }
+            script.push_str(&self.batch_quote(&self.java_path.to_string_lossy()));
+            for arg in args {
+                script.push_str(" ^\r\n  ");
+                script.push_str(&self.batch_quote(arg));
+            }
+            script.push_str("\r\n");
+            script
+        } else {
+            let mut script = String::from("#!/bin/sh\n");
+            script.push_str(&format!("# Minecraft {} - generated by PorcosLauncher\n", self.version));
+            script.push_str(&format!("# Set {} before running to play online\n", ACCESS_TOKEN_VAR));
+            script.push_str(&format!("cd {} || exit 1\n", self.shell_quote(&self.working_dir.to_string_lossy())));
+            for (key, value) in &self.env {
+                script.push_str(&format!("export {}={}\n", key, self.shell_quote(value)));
+            }
+            script.push_str("exec ");
+            for arg in &self.wrapper {
+                script.push_str(&self.shell_quote(arg));
+                script.push(' ');
+            }
+            script.push_str(&self.shell_quote(&self.java_path.to_string_lossy()));
+            for arg in args {
+                script.push_str(" \\\n  ");
+                script.push_str(&self.shell_quote(arg));
+            }
+            script.push('\n');
+            script
+        }
+    }
+
+    /// Single-quote for sh, with secrets swapped for the token variable
+    fn shell_quote(&self, arg: &str) -> String {
+        let marker = "\u{0}";
+        self.replace_secrets(arg, marker)
+            .split(marker)
+            .map(|part| format!("'{}'", part.replace('\'', "'\\''")))
+            .collect::<Vec<_>>()
+            .join(&format!("\"${{{}}}\"", ACCESS_TOKEN_VAR))
+    }
+
+    /// Double-quote for cmd.exe, with secrets swapped for the token variable
+    fn batch_quote(&self, arg: &str) -> String {
+        let marker = "\u{0}";
+        let quoted = self.replace_secrets(arg, marker)
+            .split(marker)
+            .map(|part| part.replace('%', "%%").replace('"', "\"\""))
+            .collect::<Vec<_>>()
+            .join(&format!("%{}%", ACCESS_TOKEN_VAR));
+        format!("\"{}\"", quoted)
+    }
+}
@@ -0,0 +1,227 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Window};
+use tokio::sync::Notify;
+use crate::error::{LauncherError, LauncherResult};
+
+/// The steps of a launch, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LaunchPhase {
+    Manifest,
+    Loader,
+    Assets,
+    Client,
+    Libraries,
+    Natives,
+    Java,
+    Spawn,
+}
+
+impl LaunchPhase {
+    const ALL: [LaunchPhase; 8] = [
+        Self::Manifest,
+        Self::Loader,
+        Self::Assets,
+        Self::Client,
+        Self::Libraries,
+        Self::Natives,
+        Self::Java,
+        Self::Spawn,
+    ];
+
+    /// Share of the overall progress bar; the weights add up to 100
+    fn weight(self) -> f64 {
+        match self {
+            Self::Manifest => 3.0,
+            Self::Loader => 7.0,
+            Self::Assets => 40.0,
+            Self::Client => 8.0,
+            Self::Libraries => 30.0,
+            Self::Natives => 4.0,
+            Self::Java => 3.0,
+            Self::Spawn => 5.0,
+        }
+    }
+
+    /// Overall progress at which this phase begins
+    fn offset(self) -> f64 {
+        Self::ALL.iter()
+            .take_while(|phase| **phase != self)
+            .map(|phase| phase.weight())
+            .sum()
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Manifest => "Obteniendo detalles de versión...",
+            Self::Loader => "Preparando mod loader...",
+            Self::Assets => "Descargando assets...",
+            Self::Client => "Descargando cliente...",
+            Self::Libraries => "Procesando librerías...",
+            Self::Natives => "Extrayendo nativos...",
+            Self::Java => "Buscando Java...",
+            Self::Spawn => "Iniciando proceso del juego...",
+        }
+    }
+}
+
+/// Payload of the `launch-progress` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchProgress {
+    pub launch_id: String,
+    /// `None` once the game is running
+    pub phase: Option<LaunchPhase>,
+    pub stage: String,
+    /// Overall progress, 0-100
+    pub progress: f64,
+    /// Progress inside the current phase, 0-100
+    pub phase_progress: f64,
+    pub current: u64,
+    pub total: u64,
+}
+
+#[derive(Default)]
+struct CancelFlag {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+/// Progress reporting and cancellation for one launch (or verification) run
+#[derive(Clone)]
+pub struct LaunchTask {
+    id: String,
+    window: Option<Window>,
+    cancel: Arc<CancelFlag>,
+}
+
+impl LaunchTask {
+    pub fn new(window: Option<Window>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            window,
+            cancel: Arc::new(CancelFlag::default()),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.cancelled.store(true, Ordering::SeqCst);
+        self.cancel.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Fail with a `CANCELLED` error if the launch has been cancelled
+    pub fn check(&self) -> LauncherResult<()> {
+        if self.is_cancelled() {
+            return Err(LauncherError::cancelled("Launch cancelled"));
+        }
+        Ok(())
+    }
+
+    async fn cancelled(&self) {
+        loop {
+            let notified = self.cancel.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Run one phase, dropping its future as soon as the launch is cancelled
+    pub async fn run<T>(&self, phase: LaunchPhase, work: impl Future<Output = LauncherResult<T>>) -> LauncherResult<T> {
+        self.progress(phase, phase.label(), 0, 1);
+        let result = self.cancellable(work).await?;
+        self.progress(phase, phase.label(), 1, 1);
+        Ok(result)
+    }
+
+    /// Await `work` unless the task is cancelled first
+    pub async fn cancellable<T>(&self, work: impl Future<Output = LauncherResult<T>>) -> LauncherResult<T> {
+        self.check()?;
+        tokio::select! {
+            result = work => result,
+            _ = self.cancelled() => Err(LauncherError::cancelled("Launch cancelled")),
+        }
+    }
+
+    pub fn progress(&self, phase: LaunchPhase, stage: &str, current: u64, total: u64) {
+        let fraction = if total > 0 { (current as f64 / total as f64).min(1.0) } else { 0.0 };
+        self.emit(LaunchProgress {
+            launch_id: self.id.clone(),
+            phase: Some(phase),
+            stage: stage.to_string(),
+            progress: phase.offset() + phase.weight() * fraction,
+            phase_progress: fraction * 100.0,
+            current,
+            total,
+        });
+    }
+
+    /// Report the end of the launch (the game window is up)
+    pub fn complete(&self, stage: &str) {
+        self.emit(LaunchProgress {
+            launch_id: self.id.clone(),
+            phase: None,
+            stage: stage.to_string(),
+            progress: 100.0,
+            phase_progress: 100.0,
+            current: 100,
+            total: 100,
+        });
+    }
+
+    fn emit(&self, event: LaunchProgress) {
+        if let Some(window) = &self.window {
+            let _ = window.emit("launch-progress", event);
+        }
+    }
+}
+
+/// Launches in progress, kept in Tauri state so `cancel_launch` can reach them
+#[derive(Default)]
+pub struct LaunchRegistry {
+    tasks: Mutex<HashMap<String, LaunchTask>>,
+}
+
+impl LaunchRegistry {
+    /// Track a task until the returned guard is dropped
+    pub fn register(&self, task: &LaunchTask) -> Registration<'_> {
+        self.tasks.lock().unwrap().insert(task.id.clone(), task.clone());
+        Registration { registry: self, id: task.id.clone() }
+    }
+
+    /// Cancel one launch, or every running launch when no id is given. Returns the cancelled ids.
+    pub fn cancel(&self, id: Option<&str>) -> Vec<String> {
+        let tasks = self.tasks.lock().unwrap();
+        tasks.values()
+            .filter(|task| id.is_none_or(|id| task.id == id))
+            .map(|task| {
+                task.cancel();
+                task.id.clone()
+            })
+            .collect()
+    }
+}
+
+pub struct Registration<'a> {
+    registry: &'a LaunchRegistry,
+    id: String,
+}
+
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.registry.tasks.lock().unwrap().remove(&self.id);
+    }
+}
@@ -1,20 +1,286 @@
-use std::path::PathBuf;
+use futures::stream::{self, StreamExt};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use crate::error::{LauncherError, LauncherResult};
+use crate::launcher::download_manager::{DownloadManager, DownloadRequest, Priority};
+use crate::launcher::launch_task::{LaunchPhase, LaunchTask};
+use crate::launcher::integrity::{self, VerifyReport};
+use crate::launcher::launch_plan::{ClasspathEntry, NativeEntry};
+use crate::launcher::version_details::Library;
+use crate::launcher::VersionDetails;
+
+/// Tried in order for libraries without a download URL, or whose URL fails
+const MAVEN_REPOSITORIES: &[&str] = &[
+    "https://libraries.minecraft.net/",
+    "https://maven.fabricmc.net/",
+    "https://maven.quiltmc.org/repository/release/",
+    "https://maven.minecraftforge.net/",
+    "https://maven.neoforged.net/releases/",
+    "https://repo1.maven.org/maven2/",
+];
+
+/// A jar the version needs, with every URL it may be fetched from
+#[derive(Debug, Clone)]
+struct LibraryJob {
+    name: String,
+    path: PathBuf,
+    urls: Vec<String>,
+    sha1: Option<String>,
+    size: Option<u64>,
+    /// Native jars are extracted instead of going on the classpath
+    native_excludes: Option<Vec<String>>,
+    /// Jars listed with explicit downloads must exist; maven-style entries can be
+    /// generated locally by loader installers, so a failed download is only a warning
+    required: bool,
+}
+
+/// Native jar to extract into the version's natives directory
+#[derive(Debug, Clone)]
+pub struct NativeJar {
+    pub path: PathBuf,
+    pub excludes: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct ResolvedLibraries {
+    /// Library jars in version JSON order (the client jar is not included)
+    pub classpath: Vec<PathBuf>,
+    pub natives: Vec<NativeJar>,
+    pub report: VerifyReport,
+}
 
-#[allow(dead_code)]
 pub struct LibraryManager {
     libraries_dir: PathBuf,
+    natives_jars_dir: PathBuf,
+    task: LaunchTask,
+    downloads: DownloadManager,
 }
 
-#[allow(dead_code)]
 impl LibraryManager {
-    pub fn new(libraries_dir: PathBuf) -> Self {
-        Self { libraries_dir }
+    pub fn new(libraries_dir: PathBuf, natives_jars_dir: PathBuf, task: LaunchTask, downloads: DownloadManager) -> Self {
+        Self { libraries_dir, natives_jars_dir, task, downloads }
+    }
+
+    /// Download (or repair) every library that applies to this OS and return the classpath
+    pub async fn resolve(&self, libraries: &[Library]) -> LauncherResult<ResolvedLibraries> {
+        let jobs = self.plan(libraries);
+        let total = jobs.len() as u64;
+        println!("[LibraryManager] Resolving {} library files...", total);
+
+        std::fs::create_dir_all(&self.libraries_dir)
+            .map_err(|e| LauncherError::io_at(&self.libraries_dir, format!("Failed to create libraries directory: {}", e)))?;
+        std::fs::create_dir_all(&self.natives_jars_dir)
+            .map_err(|e| LauncherError::io_at(&self.natives_jars_dir, format!("Failed to create natives jars directory: {}", e)))?;
+
+        // Bounded like the downloads themselves, so hash checks don't flood the blocking pool
+        let done = Arc::new(AtomicU64::new(0));
+        let mut outcomes: Vec<(usize, LauncherResult<bool>)> = stream::iter(jobs.clone().into_iter().enumerate())
+            .map(|(index, job)| {
+                let done = done.clone();
+                async move {
+                    let result = self.ensure(&job).await;
+                    let current = done.fetch_add(1, Ordering::Relaxed) + 1;
+                    self.task.progress(LaunchPhase::Libraries, &format!("Librería: {}", job.name), current, total);
+                    (index, result)
+                }
+            })
+            .buffer_unordered(self.downloads.max_concurrent())
+            .collect()
+            .await;
+        // Back to version JSON order, the classpath depends on it
+        outcomes.sort_by_key(|(index, _)| *index);
+
+        let mut resolved = ResolvedLibraries::default();
+        resolved.report.checked = total;
+        for (job, (_, outcome)) in jobs.into_iter().zip(outcomes) {
+            match outcome {
+                Ok(repaired) => {
+                    if repaired {
+                        resolved.report.repaired.push(job.name.clone());
+                    }
+                }
+                Err(e) if job.required => return Err(e),
+                Err(e) => {
+                    println!("[LibraryManager] WARNING: {} unavailable: {}", job.name, e);
+                    resolved.report.failed.push(job.name.clone());
+                    if !job.path.exists() {
+                        continue;
+                    }
+                }
+            }
+
+            match job.native_excludes {
+                Some(excludes) => {
+                    // Natives stay on the classpath too, older LWJGL versions load them from there
+                    resolved.classpath.push(job.path.clone());
+                    resolved.natives.push(NativeJar { path: job.path, excludes });
+                }
+                None => resolved.classpath.push(job.path),
+            }
+        }
+
+        println!("[LibraryManager] {} libraries on the classpath, {} natives, {} repaired",
+            resolved.classpath.len(), resolved.natives.len(), resolved.report.repaired.len());
+        Ok(resolved)
+    }
+
+    /// Report the state of every library for this OS without downloading anything
+    pub async fn inspect(&self, libraries: &[Library]) -> (Vec<ClasspathEntry>, Vec<NativeEntry>) {
+        let jobs = self.plan(libraries);
+        let checked = tokio::task::spawn_blocking(move || {
+            jobs.into_iter()
+                .map(|job| {
+                    let status = integrity::file_status(&job.path, job.sha1.as_deref(), job.size);
+                    (job, status)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        .unwrap_or_default();
+
+        let mut classpath = Vec::new();
+        let mut natives = Vec::new();
+        for (job, status) in checked {
+            classpath.push(ClasspathEntry { path: job.path.clone(), status });
+            if let Some(excludes) = job.native_excludes {
+                natives.push(NativeEntry { path: job.path, status, excludes });
+            }
+        }
+        (classpath, natives)
     }
 
-    // TODO: Implement library downloading
-    pub async fn download_libraries(&self, version: &str) -> Result<(), String> {
-        println!("[LibraryManager] Downloading libraries for version {}...", version);
-        // Placeholder
-        Ok(())
+    /// Make sure one file is present and intact. Returns whether it had to be downloaded.
+    async fn ensure(&self, job: &LibraryJob) -> LauncherResult<bool> {
+        let path = job.path.clone();
+        let (sha1, size) = (job.sha1.clone(), job.size);
+        let valid = tokio::task::spawn_blocking(move || integrity::is_valid(&path, sha1.as_deref(), size))
+            .await
+            .unwrap_or(false);
+        if valid {
+            return Ok(false);
+        }
+
+        if job.urls.is_empty() {
+            return Err(LauncherError::io_at(&job.path, format!("{} has no download URL and is not installed", job.name)));
+        }
+
+        let request = DownloadRequest::new(job.urls[0].clone(), &job.path)
+            .fallbacks(job.urls[1..].to_vec())
+            .checksum(job.sha1.clone(), job.size)
+            .priority(Priority::High)
+            .group(self.task.id());
+        self.downloads.fetch(request).await?;
+        Ok(true)
+    }
+
+    /// Turn the version's library list into download jobs for the current OS
+    fn plan(&self, libraries: &[Library]) -> Vec<LibraryJob> {
+        let mut jobs = Vec::new();
+
+        for library in libraries {
+            if !VersionDetails::should_use_library(library) {
+                continue;
+            }
+
+            let downloads = match &library.downloads {
+                Some(downloads) => downloads,
+                None => {
+                    // Maven style: the library's own repository first, then the known ones
+                    let relative = maven_path(&library.name);
+                    jobs.push(LibraryJob {
+                        name: library.name.clone(),
+                        path: self.libraries_dir.join(&relative),
+                        urls: repository_urls(library.url.as_deref(), &relative),
+                        sha1: None,
+                        size: None,
+                        native_excludes: None,
+                        required: false,
+                    });
+                    continue;
+                }
+            };
+
+            let native_key = library.natives.as_ref().and_then(|natives| {
+                #[cfg(target_os = "windows")]
+                { natives.get("windows") }
+                #[cfg(target_os = "macos")]
+                { natives.get("osx") }
+                #[cfg(target_os = "linux")]
+                { natives.get("linux") }
+            });
+            if let (Some(key), Some(classifiers)) = (native_key, &downloads.classifiers) {
+                if let Some(native) = classifiers.get(key) {
+                    let relative = native.path.clone()
+                        .map(PathBuf::from)
+                        .unwrap_or_else(|| maven_path(&format!("{}:{}", library.name, key)));
+                    jobs.push(LibraryJob {
+                        name: format!("{} ({})", library.name, key),
+                        path: self.natives_jars_dir.join(relative),
+                        urls: vec![native.url.clone()],
+                        sha1: Some(native.sha1.clone()),
+                        size: Some(native.size),
+                        native_excludes: Some(library.extract.as_ref().map(|e| e.exclude.clone()).unwrap_or_default()),
+                        required: true,
+                    });
+                }
+            }
+
+            if let Some(artifact) = &downloads.artifact {
+                let relative = artifact.path.clone()
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| maven_path(&library.name));
+                let path = self.libraries_dir.join(&relative);
+                // Loader installers list locally generated jars with an empty URL
+                let urls = if artifact.url.is_empty() {
+                    Vec::new()
+                } else {
+                    let mut urls = vec![artifact.url.clone()];
+                    urls.extend(repository_urls(None, &relative).into_iter().filter(|u| u != &artifact.url));
+                    urls
+                };
+                jobs.push(LibraryJob {
+                    name: library.name.clone(),
+                    path,
+                    required: !urls.is_empty(),
+                    urls,
+                    sha1: Some(artifact.sha1.clone()).filter(|s| !s.is_empty()),
+                    size: Some(artifact.size).filter(|s| *s > 0),
+                    native_excludes: None,
+                });
+            }
+        }
+
+        jobs
+    }
+}
+
+/// Relative path of a Maven coordinate: `group:artifact:version[:classifier][@ext]`
+pub fn maven_path(coordinate: &str) -> PathBuf {
+    let (coordinate, extension) = coordinate.split_once('@').unwrap_or((coordinate, "jar"));
+    let parts: Vec<&str> = coordinate.split(':').collect();
+    if parts.len() < 3 {
+        return PathBuf::from(format!("{}.{}", coordinate, extension));
+    }
+
+    let group = parts[0].replace('.', "/");
+    let (artifact, version) = (parts[1], parts[2]);
+    let file_name = match parts.get(3) {
+        Some(classifier) => format!("{}-{}-{}.{}", artifact, version, classifier, extension),
+        None => format!("{}-{}.{}", artifact, version, extension),
+    };
+    PathBuf::from(group).join(artifact).join(version).join(file_name)
+}
+
+fn repository_urls(preferred: Option<&str>, relative: &Path) -> Vec<String> {
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    let mut urls: Vec<String> = Vec::new();
+    for repository in preferred.into_iter().chain(MAVEN_REPOSITORIES.iter().copied()) {
+        let base = if repository.ends_with('/') { repository.to_string() } else { format!("{}/", repository) };
+        let url = format!("{}{}", base, relative);
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
     }
+    urls
 }
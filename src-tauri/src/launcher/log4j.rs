@@ -0,0 +1,144 @@
+use serde::Serialize;
+use crate::launcher::console::LogLevel;
+
+/// Events bigger than this are not log4j output after all; give up and pass them through
+const MAX_EVENT_SIZE: usize = 1024 * 1024;
+
+/// One event from the game's log4j2 XML layout
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogRecord {
+    /// Unix time in milliseconds
+    pub timestamp: u64,
+    pub thread: String,
+    pub level: Option<LogLevel>,
+    pub logger: String,
+    pub message: String,
+    pub throwable: Option<String>,
+}
+
+impl LogRecord {
+    /// Console text in the game's usual `[thread/LEVEL]: message` layout
+    pub fn to_text(&self) -> String {
+        let level = self.level.map(|level| format!("{:?}", level).to_uppercase()).unwrap_or_default();
+        let mut text = format!("[{}/{}]: {}", self.thread, level, self.message);
+        if let Some(throwable) = &self.throwable {
+            text.push('\n');
+            text.push_str(throwable.trim_end());
+        }
+        text
+    }
+
+    /// The game has finished loading and is about to show the title screen
+    pub fn is_ready_marker(&self) -> bool {
+        self.message.contains("Sound engine started")
+            || (self.message.starts_with("Created:") && self.message.contains("-atlas"))
+    }
+}
+
+#[derive(Debug)]
+pub enum LogLine {
+    /// Anything printed outside log4j (JVM messages, mods writing to System.out)
+    Plain(String),
+    Record(LogRecord),
+}
+
+/// Turns the game's stdout, when it uses the XML layout, into log records.
+/// Lines are fed one at a time since an event spans several of them.
+#[derive(Default)]
+pub struct Log4jParser {
+    event: Option<String>,
+}
+
+impl Log4jParser {
+    pub fn feed(&mut self, line: &str) -> Vec<LogLine> {
+        let mut lines = Vec::new();
+
+        let event = match self.event.take() {
+            Some(mut event) => {
+                event.push('\n');
+                event.push_str(line);
+                event
+            }
+            None => match line.find("<log4j:Event") {
+                Some(start) => {
+                    // Text before the tag was printed without a newline by someone else
+                    if !line[..start].trim().is_empty() {
+                        lines.push(LogLine::Plain(line[..start].to_string()));
+                    }
+                    line[start..].to_string()
+                }
+                None => {
+                    lines.push(LogLine::Plain(line.to_string()));
+                    return lines;
+                }
+            },
+        };
+
+        match event.find("</log4j:Event>") {
+            Some(end) => {
+                let (xml, rest) = event.split_at(end + "</log4j:Event>".len());
+                match parse_event(xml) {
+                    Some(record) => lines.push(LogLine::Record(record)),
+                    None => lines.extend(xml.lines().map(|line| LogLine::Plain(line.to_string()))),
+                }
+                if !rest.trim().is_empty() {
+                    lines.extend(self.feed(rest));
+                }
+            }
+            None if event.len() > MAX_EVENT_SIZE => {
+                lines.extend(event.lines().map(|line| LogLine::Plain(line.to_string())));
+            }
+            None => self.event = Some(event),
+        }
+        lines
+    }
+}
+
+fn parse_event(xml: &str) -> Option<LogRecord> {
+    let tag_end = xml.find('>')?;
+    let tag = &xml[..tag_end];
+
+    Some(LogRecord {
+        timestamp: attribute(tag, "timestamp").and_then(|t| t.parse().ok()).unwrap_or(0),
+        thread: attribute(tag, "thread").unwrap_or_default(),
+        level: attribute(tag, "level").and_then(|level| LogLevel::parse(&level)),
+        logger: attribute(tag, "logger").unwrap_or_default(),
+        message: element(xml, "log4j:Message").unwrap_or_default(),
+        throwable: element(xml, "log4j:Throwable"),
+    })
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let key = format!(" {}=\"", name);
+    let start = tag.find(&key)? + key.len();
+    let end = tag[start..].find('"')? + start;
+    Some(unescape(&tag[start..end]))
+}
+
+fn element(xml: &str, name: &str) -> Option<String> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&close)? + start;
+    let content = xml[start..end].trim();
+
+    Some(match content.strip_prefix("<![CDATA[").and_then(|c| c.strip_suffix("]]>")) {
+        // A message containing "]]>" is split into several CDATA sections
+        Some(cdata) => cdata.replace("]]><![CDATA[", ""),
+        None => unescape(content),
+    })
+}
+
+fn unescape(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#13;", "\r")
+        .replace("&#10;", "\n")
+        .replace("&amp;", "&")
+}
@@ -1,10 +1,21 @@
-use crate::launcher::VersionDetails;
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
-use tauri::{Emitter, Window};
+use tauri::{Emitter, Manager, Window};
+use crate::error::{LauncherError, LauncherResult};
+use crate::launcher::integrity::{self, FileStatus, VerifyReport};
+use crate::launcher::download_manager::{DownloadManager, DownloadRequest, Priority};
+use crate::launcher::console::{ConsoleHistory, GameOutput};
+use crate::launcher::game_log::{OutputStream, SessionLog};
+use crate::launcher::launch_plan::{ClasspathEntry, LaunchPlan, NativeEntry};
+use crate::launcher::launch_task::{LaunchPhase, LaunchTask};
+use crate::launcher::library_manager::LibraryManager;
+use crate::launcher::log4j::{Log4jParser, LogLine};
+use crate::launcher::process_registry::{self, ProcessRegistry, RunningGame};
+use crate::launcher::shell_words;
 // use std::process::Child;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub enum AuthData {
     Microsoft {
         access_token: String,
@@ -17,6 +28,28 @@ pub enum AuthData {
         username: String,
         xuid: Option<String>,
     },
+    /// Third-party Yggdrasil account, launched through authlib-injector
+    Yggdrasil {
+        access_token: String,
+        uuid: String,
+        username: String,
+        api_root: String,
+    },
+}
+
+/// Access tokens are left out so the options can be logged.
+impl std::fmt::Debug for AuthData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, uuid, username) = match self {
+            AuthData::Microsoft { uuid, username, .. } => ("Microsoft", uuid, username),
+            AuthData::Offline { uuid, username, .. } => ("Offline", uuid, username),
+            AuthData::Yggdrasil { uuid, username, .. } => ("Yggdrasil", uuid, username),
+        };
+        f.debug_struct(kind)
+            .field("uuid", uuid)
+            .field("username", username)
+            .finish_non_exhaustive()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,18 +59,126 @@ pub struct LaunchOptions {
     pub mod_loader: Option<String>,
     #[serde(rename = "modLoaderVersion", alias = "mod_loader_version")]
     pub mod_loader_version: Option<String>,
-    pub auth: AuthData,
+    /// Explicit credentials; when missing they are resolved from the account registry
+    #[serde(default)]
+    pub auth: Option<AuthData>,
+    #[serde(rename = "accountId", alias = "account_id", default)]
+    pub account_id: Option<String>,
     #[serde(rename = "memoryMin", alias = "memory_min")]
     pub memory_min: String,
     #[serde(rename = "memoryMax", alias = "memory_max")]
     pub memory_max: String,
     #[serde(rename = "javaPath", alias = "java_path")]
     pub java_path: Option<PathBuf>,
+    /// Game directory of the instance (saves, mods, options); defaults to .minecraft
     #[serde(rename = "minecraftDir", alias = "minecraft_dir")]
     pub minecraft_dir: Option<PathBuf>,
+    /// Where versions, libraries and assets are stored; defaults to the shared store
+    #[serde(rename = "contentDir", alias = "content_dir", default)]
+    pub content_dir: Option<PathBuf>,
+    /// Key for the running-game registry; defaults to the game directory's folder name
+    #[serde(rename = "instanceId", alias = "instance_id", default)]
+    pub instance_id: Option<String>,
+    /// Launch even if this instance is already running
+    #[serde(default)]
+    pub force: bool,
+    /// Serve offline accounts (and their skins) through the embedded Yggdrasil server.
+    /// Local-only: other machines on a LAN can't verify against it or see the skin.
+    #[serde(rename = "localYggdrasil", alias = "local_yggdrasil", default)]
+    pub local_yggdrasil: bool,
+    /// authlib-injector jar to use instead of the bundled or downloaded one
+    #[serde(rename = "authlibInjectorPath", alias = "authlib_injector_path", default)]
+    pub authlib_injector_path: Option<PathBuf>,
+    /// Window size; the game's default (854x480) is used unless both are set
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default)]
+    pub fullscreen: bool,
+    #[serde(default)]
+    pub demo: bool,
+    /// Join a world, server or realm as soon as the game has loaded
+    #[serde(rename = "quickPlay", alias = "quick_play", default)]
+    pub quick_play: Option<QuickPlay>,
+    #[serde(flatten)]
+    pub customization: LaunchCustomization,
+}
+
+/// User additions to the launch command, set per instance or per launch.
+/// Arguments and the wrapper are split like a POSIX shell would (`-Dname="a b"`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LaunchCustomization {
+    /// Appended after the version's JVM arguments, so they win over its defaults
+    #[serde(alias = "jvm_args")]
+    pub jvm_args: Option<String>,
+    #[serde(alias = "game_args")]
+    pub game_args: Option<String>,
+    #[serde(alias = "env_vars")]
+    pub env_vars: HashMap<String, String>,
+    /// Program Java is started through, e.g. `gamemoderun`, `prime-run`, `mangohud` or `nice -n 5`
+    #[serde(alias = "wrapper_command")]
+    pub wrapper_command: Option<String>,
+    /// Shell command run in the game directory before the game starts
+    #[serde(alias = "pre_launch_command")]
+    pub pre_launch_command: Option<String>,
+    /// Shell command run after the game exits; `MC_EXIT_CODE` holds the exit code
+    #[serde(alias = "post_exit_command")]
+    pub post_exit_command: Option<String>,
+    /// Abort the launch when the pre-launch command fails instead of only logging it
+    #[serde(alias = "abort_on_hook_failure")]
+    pub abort_on_hook_failure: bool,
+}
+
+impl LaunchCustomization {
+    /// Fill whatever these options leave unset from `defaults` (the instance settings)
+    pub fn with_defaults(self, defaults: &LaunchCustomization) -> Self {
+        let mut env_vars = defaults.env_vars.clone();
+        env_vars.extend(self.env_vars);
+        Self {
+            jvm_args: self.jvm_args.or_else(|| defaults.jvm_args.clone()),
+            game_args: self.game_args.or_else(|| defaults.game_args.clone()),
+            env_vars,
+            wrapper_command: self.wrapper_command.or_else(|| defaults.wrapper_command.clone()),
+            pre_launch_command: self.pre_launch_command.or_else(|| defaults.pre_launch_command.clone()),
+            post_exit_command: self.post_exit_command.or_else(|| defaults.post_exit_command.clone()),
+            abort_on_hook_failure: self.abort_on_hook_failure || defaults.abort_on_hook_failure,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
+/// Where the game goes straight after the title screen
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum QuickPlay {
+    /// Folder name of a world in `saves`
+    Singleplayer { world: String },
+    /// `host[:port]`
+    Multiplayer { server: String },
+    Realms { realm: String },
+}
+
+impl LaunchOptions {
+    fn custom_resolution(&self) -> Option<(u32, u32)> {
+        self.width.zip(self.height)
+    }
+
+    /// Value of a `features` flag used by the argument rules of the version JSON
+    fn has_feature(&self, feature: &str) -> bool {
+        match feature {
+            "is_demo_user" => self.demo,
+            "has_custom_resolution" => self.custom_resolution().is_some(),
+            "is_quick_play_singleplayer" => matches!(self.quick_play, Some(QuickPlay::Singleplayer { .. })),
+            "is_quick_play_multiplayer" => matches!(self.quick_play, Some(QuickPlay::Multiplayer { .. })),
+            "is_quick_play_realms" => matches!(self.quick_play, Some(QuickPlay::Realms { .. })),
+            // has_quick_plays_support only adds the quick play log, which we don't read
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
 struct LoaderManifest {
     id: String,
     #[serde(rename = "inheritsFrom")]
@@ -48,6 +189,33 @@ struct LoaderManifest {
     arguments: Option<crate::launcher::version_details::Arguments>,
 }
 
+/// authlib-injector build that is downloaded when no local jar is available (1.2.5)
+const AUTHLIB_INJECTOR_BUILD: u32 = 53;
+
+/// `artifact/<build>.json` from the authlib-injector download API
+#[derive(Debug, Serialize, Deserialize)]
+struct AuthlibInjectorArtifact {
+    build_number: u32,
+    version: String,
+    download_url: String,
+    checksums: AuthlibInjectorChecksums,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AuthlibInjectorChecksums {
+    sha256: String,
+}
+
+/// authlib-injector agent settings for launching against a Yggdrasil server
+#[derive(Debug, Clone)]
+pub struct AuthlibInjectorConfig {
+    pub api_root: String,
+    /// Replaces `${auth_access_token}` when the server issued its own token
+    pub access_token: Option<String>,
+    /// Replaces `${user_properties}` (legacy property map JSON)
+    pub user_properties: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct LaunchResult {
     pub success: bool,
@@ -55,14 +223,6 @@ pub struct LaunchResult {
     pub process_id: Option<u32>,
 }
 
-#[derive(Clone, Serialize)]
-struct ProgressEvent {
-    stage: String,
-    progress: f64,
-    total: u64,
-    current: u64,
-}
-
 #[derive(Clone, Serialize)]
 struct CrashReportEvent {
     path: String,
@@ -70,82 +230,161 @@ struct CrashReportEvent {
 }
 
 pub struct MinecraftLauncher {
-    minecraft_dir: PathBuf,
+    /// The instance's own folder: saves, mods, options, logs, crash reports
+    game_dir: PathBuf,
+    /// Shared by every instance: versions, libraries, assets and native jars
+    content_dir: PathBuf,
     window: Option<Window>,
     http_client: reqwest::Client,
+    downloads: DownloadManager,
+    processes: ProcessRegistry,
+    console: ConsoleHistory,
+    task: LaunchTask,
+    authlib_injector: Option<AuthlibInjectorConfig>,
 }
 
 impl MinecraftLauncher {
-    pub fn new(minecraft_dir: PathBuf, window: Option<Window>) -> Self {
+    pub fn new(game_dir: PathBuf, window: Option<Window>) -> Self {
         let http_client = reqwest::Client::builder()
             .user_agent("PorcosLauncher/1.0")
             .timeout(std::time::Duration::from_secs(30))
             .build()
             .unwrap_or_default();
             
-        Self { minecraft_dir, window, http_client }
+        // Share the app's download queue when there is one
+        let downloads = window.as_ref()
+            .and_then(|window| window.try_state::<DownloadManager>())
+            .map(|state| state.inner().clone())
+            .unwrap_or_default();
+        let processes = window.as_ref()
+            .and_then(|window| window.try_state::<ProcessRegistry>())
+            .map(|state| state.inner().clone())
+            .unwrap_or_default();
+        let console = window.as_ref()
+            .and_then(|window| window.try_state::<ConsoleHistory>())
+            .map(|state| state.inner().clone())
+            .unwrap_or_default();
+            
+        let task = LaunchTask::new(window.clone());
+        let content_dir = Self::get_shared_content_dir();
+        Self { game_dir, content_dir, window, http_client, downloads, processes, console, task, authlib_injector: None }
     }
 
-    #[allow(dead_code)]
-    pub fn get_minecraft_dir(&self) -> &PathBuf {
-        &self.minecraft_dir
+    /// Keep versions, libraries and assets somewhere other than the shared store
+    pub fn with_content_dir(mut self, content_dir: PathBuf) -> Self {
+        self.content_dir = content_dir;
+        self
     }
 
-    fn emit_progress(&self, stage: &str, current: u64, total: u64, percent: f64) {
-        if let Some(window) = &self.window {
-            let event = ProgressEvent {
-                stage: stage.to_string(),
-                progress: percent,
-                total,
-                current,
-            };
-            
-            let _ = window.emit("launch-progress", event);
-        }
+    /// Report progress through (and take cancellation from) an existing task
+    pub fn with_task(mut self, task: LaunchTask) -> Self {
+        self.task = task;
+        self
     }
 
-    pub async fn launch(&self, options: LaunchOptions) -> Result<LaunchResult, String> {
-        println!("[MinecraftLauncher] ========== LAUNCHING MINECRAFT ==========");
+    pub fn task(&self) -> &LaunchTask {
+        &self.task
+    }
+
+    /// Launch through authlib-injector so the game talks to the given Yggdrasil server
+    pub fn with_authlib_injector(mut self, config: AuthlibInjectorConfig) -> Self {
+        self.authlib_injector = Some(config);
+        self
+    }
+
+    /// Registry key of the instance being launched
+    pub fn instance_id(&self, options: &LaunchOptions) -> String {
+        options.instance_id.clone().unwrap_or_else(|| {
+            self.game_dir.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| self.game_dir.to_string_lossy().to_string())
+        })
+    }
+
+    #[allow(dead_code)]
+    pub fn get_minecraft_dir(&self) -> &PathBuf {
+        &self.game_dir
+    }
+
+    /// Resolve everything the launch needs without starting the game. With `prepare`
+    /// missing files are downloaded and natives extracted; without it nothing is written
+    /// and the plan reports which files are missing or corrupt.
+    pub async fn plan(&self, options: &LaunchOptions, prepare: bool) -> LauncherResult<LaunchPlan> {
         println!("[MinecraftLauncher] Version: {}", options.version);
         if let Some(loader) = &options.mod_loader {
             println!("[MinecraftLauncher] Mod Loader: {} ({})", loader, options.mod_loader_version.as_deref().unwrap_or("?"));
         }
-        println!("[MinecraftLauncher] Auth: {:?}", options.auth);
+        let auth = options.auth.clone().ok_or_else(|| LauncherError::Auth { code: "NO_ACCOUNT".into(), message: "No account selected for launch".into() })?;
+        // A dry run never refreshes the session and redacts the token anyway
+        if let (true, AuthData::Microsoft { access_token, .. }) = (prepare, &auth) {
+            if crate::auth::token::is_expired(access_token) {
+                return Err(LauncherError::Auth { code: "TOKEN_EXPIRED".into(), message: "The Microsoft access token has expired. Refresh the session before launching.".into() });
+            }
+        }
+        println!("[MinecraftLauncher] Auth: {:?}", auth);
         println!("[MinecraftLauncher] Memory: {} - {}", options.memory_min, options.memory_max);
         
-        self.emit_progress("Iniciando lanzador...", 0, 100, 0.0);
-
-        // 1. Get version manifest to find version URL
+        // 1. Resolve the version JSON (cached manifest / installed copy when offline)
         use crate::launcher::version_manager::VersionManager;
-        let cache_dir = self.minecraft_dir.join("versions");
-        let version_manager = VersionManager::new(cache_dir.clone());
-        
-        println!("[MinecraftLauncher] Fetching version manifest...");
-        self.emit_progress("Obteniendo manifiesto de versiones...", 5, 100, 1.0);
-        let manifest = version_manager.fetch_version_manifest().await?;
+        let cache_dir = self.content_dir.join("versions");
+        let mut pending = Vec::new();
         
-        let version_info = manifest.versions.iter()
-            .find(|v| v.id == options.version)
-            .ok_or_else(|| format!("Version {} not found", options.version))?;
-        
-        println!("[MinecraftLauncher] Version URL: {}", version_info.url);
-        
-        // Fetch full version details
-        self.emit_progress("Obteniendo detalles de versión...", 10, 100, 2.0);
-        let mut version_details = version_manager.fetch_version_details(&version_info.url).await?;
-        let version_dir = self.minecraft_dir.join("versions").join(&options.version);
-        std::fs::create_dir_all(&version_dir).map_err(|e| format!("Failed to create version dir: {}", e))?;
+        println!("[MinecraftLauncher] Resolving version {}...", options.version);
+        let mut version_details = if prepare {
+            let version_manager = VersionManager::new(cache_dir.clone());
+            self.task.run(LaunchPhase::Manifest, version_manager.get_version_details(&options.version)).await?
+        } else {
+            match VersionManager::new(cache_dir.clone()).installed_version_details(&options.version)? {
+                Some(details) => details,
+                None => {
+                    // Fetched to plan the rest, but the manifest and version JSON are not saved
+                    let version_manager = VersionManager::new(cache_dir.clone()).read_only();
+                    let details = self.task.run(LaunchPhase::Manifest, version_manager.get_version_details(&options.version)).await?;
+                    pending.push(format!("Version {} is not installed yet; its version JSON is downloaded when the game is launched", options.version));
+                    details
+                }
+            }
+        };
+        let version_dir = self.content_dir.join("versions").join(&options.version);
+        if prepare {
+            std::fs::create_dir_all(&version_dir).map_err(|e| LauncherError::io(format!("Failed to create version dir: {}", e)))?;
+        }
 
         // --- MOD LOADER HANDLING ---
         if let (Some(loader), Some(loader_version)) = (&options.mod_loader, &options.mod_loader_version) {
-            self.emit_progress(&format!("Preparando {}...", loader), 15, 100, 5.0);
-            let loader_manifest = match loader.to_lowercase().as_str() {
-                "fabric" => Some(self.get_fabric_profile(&options.version, loader_version).await?),
-                "quilt" => Some(self.get_quilt_profile(&options.version, loader_version).await?),
-                "neoforge" => Some(self.get_neoforge_profile(&options.version, loader_version).await?),
-                "forge" => Some(self.get_forge_profile(&options.version, loader_version).await?),
-                "vanilla" => None,
-                _ => None,
+            let loader = loader.to_lowercase();
+            let profile_path = version_dir.join(format!("{}-{}.json", loader, loader_version));
+            let loader_manifest = if prepare {
+                let manifest = self.task.run(LaunchPhase::Loader, async {
+                    self.task.progress(LaunchPhase::Loader, &format!("Preparando {}...", loader), 0, 1);
+                    Ok(match loader.as_str() {
+                        "fabric" => Some(self.get_fabric_profile(&options.version, loader_version).await?),
+                        "quilt" => Some(self.get_quilt_profile(&options.version, loader_version).await?),
+                        "neoforge" => Some(self.get_neoforge_profile(&options.version, loader_version).await?),
+                        "forge" => Some(self.get_forge_profile(&options.version, loader_version).await?),
+                        "vanilla" => None,
+                        _ => None,
+                    })
+                }).await?;
+                // Kept so dry runs can plan the loader without running its installer
+                if let Some(manifest) = &manifest {
+                    if let Err(e) = serde_json::to_string(manifest).map_err(LauncherError::from)
+                        .and_then(|json| std::fs::write(&profile_path, json).map_err(LauncherError::from))
+                    {
+                        println!("[MinecraftLauncher] WARNING: Failed to save {} profile: {}", loader, e);
+                    }
+                }
+                manifest
+            } else if loader == "vanilla" {
+                None
+            } else {
+                match std::fs::read_to_string(&profile_path).ok().and_then(|json| serde_json::from_str::<LoaderManifest>(&json).ok()) {
+                    Some(manifest) => Some(manifest),
+                    None => {
+                        pending.push(format!("{} {} is not installed yet; it is installed when the game is launched", loader, loader_version));
+                        None
+                    }
+                }
             };
 
             if let Some(manifest) = loader_manifest {
@@ -183,177 +422,89 @@ impl MinecraftLauncher {
 
         // Initialize AssetManager
         use crate::launcher::asset_manager::AssetManager;
-        let assets_dir = self.minecraft_dir.join("assets");
-        let asset_manager = AssetManager::new(assets_dir.clone(), self.window.clone());
-
-        println!("[MinecraftLauncher] Downloading assets...");
-        // AssetManager handles 0-20%
-        asset_manager.download_assets(&version_details.asset_index.id, &version_details.asset_index.url).await?;
+        let assets_dir = self.content_dir.join("assets");
+        let asset_manager = AssetManager::new(assets_dir.clone(), self.task.clone(), self.downloads.clone());
+
+        if prepare {
+            println!("[MinecraftLauncher] Downloading assets...");
+            let asset_report = self.task.run(LaunchPhase::Assets, asset_manager.download_assets(&version_details.asset_index)).await?;
+            if !asset_report.failed.is_empty() {
+                // Starting anyway would leave the game without sounds or languages
+                return Err(LauncherError::network(format!(
+                    "{} assets could not be downloaded (first: {})",
+                    asset_report.failed.len(),
+                    asset_report.failed[0]
+                )));
+            }
+        }
         
         // Download client jar
         let client_jar_path = version_dir.join(format!("{}.jar", options.version));
-        if !client_jar_path.exists() {
-             println!("[MinecraftLauncher] Downloading client jar...");
-             self.emit_progress("Descargando cliente...", 80, 100, 20.0);
-             self.download_file(&version_details.downloads.client.url, &client_jar_path).await?;
-        }
+        let client = &version_details.downloads.client;
+        let client_status = if !prepare {
+            integrity::file_status(&client_jar_path, Some(&client.sha1), Some(client.size))
+        } else {
+            if !integrity::is_valid(&client_jar_path, Some(&client.sha1), Some(client.size)) {
+                println!("[MinecraftLauncher] Downloading client jar...");
+                self.task.run(LaunchPhase::Client, self.download_verified(&client.url, &client_jar_path, Some(&client.sha1), Some(client.size))).await?;
+            }
+            FileStatus::Present
+        };
 
         // 5. Download libraries and extract natives (following nitrolaunch exactly)
         println!("[MinecraftLauncher] Processing libraries...");
-        self.emit_progress("Procesando librerías...", 85, 100, 22.0);
-        let libraries_dir = self.minecraft_dir.join("libraries");
-        let natives_dir = version_dir.join("natives");
-        let natives_jars_dir = self.minecraft_dir.join("natives"); // Separate folder for native JARs
-        
-        std::fs::create_dir_all(&libraries_dir)
-            .map_err(|e| format!("Failed to create libraries directory: {}", e))?;
-        std::fs::create_dir_all(&natives_jars_dir)
-            .map_err(|e| format!("Failed to create natives jars directory: {}", e))?;
-            
-        // Clean natives directory to ensure no leftovers
-        if natives_dir.exists() {
-            std::fs::remove_dir_all(&natives_dir)
-                .map_err(|e| format!("Failed to clean natives directory: {}", e))?;
-        }
-        std::fs::create_dir_all(&natives_dir)
-            .map_err(|e| format!("Failed to create natives directory: {}", e))?;
-        
-        let mut classpath_entries = vec![client_jar_path.to_string_lossy().to_string()];
-        
-        let total_libs = version_details.libraries.len() as u64;
-        let mut processed_libs = 0;
-
-        for library in &version_details.libraries {
-            processed_libs += 1;
-            if processed_libs % 5 == 0 {
-                let lib_percent = processed_libs as f64 / total_libs as f64;
-                // Map libraries to 20% - 90% range
-                let global_progress = 20.0 + (lib_percent * 70.0);
-                self.emit_progress("Verificando librerías...", processed_libs, total_libs, global_progress);
+        let libraries_dir = self.content_dir.join("libraries");
+        // Extracted per instance, so two instances on the same version don't wipe each other's natives
+        let natives_dir = self.game_dir.join("bin").join(&options.version);
+        let natives_jars_dir = self.content_dir.join("natives"); // Separate folder for native JARs
+        
+        let library_manager = LibraryManager::new(libraries_dir.clone(), natives_jars_dir, self.task.clone(), self.downloads.clone());
+        let mut classpath_entries = vec![ClasspathEntry { path: client_jar_path.clone(), status: client_status }];
+        let native_entries = if !prepare {
+            let (classpath, natives) = library_manager.inspect(&version_details.libraries).await;
+            classpath_entries.extend(classpath);
+            natives
+        } else {
+            // Clean natives directory to ensure no leftovers
+            if natives_dir.exists() {
+                std::fs::remove_dir_all(&natives_dir)
+                    .map_err(|e| LauncherError::io(format!("Failed to clean natives directory: {}", e)))?;
             }
+            std::fs::create_dir_all(&natives_dir)
+                .map_err(|e| LauncherError::io(format!("Failed to create natives directory: {}", e)))?;
 
-            println!("[MinecraftLauncher] Checking library: {}", library.name);
+            let resolved = self.task.run(LaunchPhase::Libraries, library_manager.resolve(&version_details.libraries)).await?;
 
-            if !VersionDetails::should_use_library(library) {
-                continue;
+            let total_natives = resolved.natives.len() as u64;
+            for (index, native) in resolved.natives.iter().enumerate() {
+                self.task.check()?;
+                self.task.progress(LaunchPhase::Natives, LaunchPhase::Natives.label(), index as u64, total_natives);
+                println!("[MinecraftLauncher] Extracting native JAR: {:?}", native.path);
+                self.extract_native(&native.path, &natives_dir, &native.excludes)?;
             }
-            
-            // Get native classifier key for this OS
-            let native_classifier_key = if let Some(natives) = &library.natives {
-                #[cfg(target_os = "windows")]
-                { natives.get("windows").map(|s| s.as_str()) }
-                #[cfg(target_os = "macos")]
-                { natives.get("osx").map(|s| s.as_str()) }
-                #[cfg(target_os = "linux")]
-                { natives.get("linux").map(|s| s.as_str()) }
-            } else {
-                None
-            };
 
-            if let Some(downloads) = &library.downloads {
-                // Check if this library has native classifiers
-                if let Some(key) = native_classifier_key {
-                    if let Some(classifiers) = &downloads.classifiers {
-                        if let Some(native_artifact) = classifiers.get(key) {
-                            println!("[MinecraftLauncher] Processing native library: {} ({})", library.name, key);
-                            
-                            // Download native jar to natives-jars directory (using the path from the artifact)
-                            let path_in_artifact = native_artifact.path.as_ref()
-                                .ok_or_else(|| format!("Native artifact missing path field: {}", library.name))?;
-                            let native_jar_path = natives_jars_dir.join(path_in_artifact);
-                            if !native_jar_path.exists() {
-                                if let Some(parent) = native_jar_path.parent() {
-                                    std::fs::create_dir_all(parent).ok();
-                                }
-                                println!("[MinecraftLauncher] Downloading native JAR from: {}", native_artifact.url);
-                                self.download_file(&native_artifact.url, &native_jar_path).await?;
-                            }
-                            
-                            // Add native JAR to classpath
-                            classpath_entries.push(native_jar_path.to_string_lossy().to_string());
-                            
-                            // Extract native jar to natives directory
-                            let excludes = library.extract.as_ref().map(|e| e.exclude.clone()).unwrap_or_default();
-                            println!("[MinecraftLauncher] Extracting native JAR: {:?}", native_jar_path);
-                            self.extract_native(&native_jar_path, &natives_dir, &excludes)?;
-                            
-                            // Also download the artifact (main JAR) if it exists
-                            if let Some(artifact) = &downloads.artifact {
-                                let path_in_artifact = artifact.path.as_ref()
-                                    .ok_or_else(|| format!("Artifact missing path field: {}", library.name))?;
-                                let lib_path = libraries_dir.join(path_in_artifact);
-                                
-                                if !lib_path.exists() {
-                                    if let Some(parent) = lib_path.parent() {
-                                        std::fs::create_dir_all(parent).ok();
-                                    }
-                                    self.download_file(&artifact.url, &lib_path).await?;
-                                }
-                                
-                                classpath_entries.push(lib_path.to_string_lossy().to_string());
-                            }
-                            
-                            continue; // Skip artifact-only processing
-                        }
-                    }
-                }
-                
-                // Regular library or library without native classifiers - just download artifact
-                if let Some(artifact) = &downloads.artifact {
-                    let path_in_artifact = artifact.path.as_ref()
-                        .ok_or_else(|| format!("Artifact missing path field: {}", library.name))?;
-                    let lib_path = libraries_dir.join(path_in_artifact);
-                    
-                    if !lib_path.exists() {
-                        if let Some(parent) = lib_path.parent() {
-                            std::fs::create_dir_all(parent).ok();
-                        }
-                        self.download_file(&artifact.url, &lib_path).await?;
-                    }
-                    
-                    classpath_entries.push(lib_path.to_string_lossy().to_string());
+            // Debug: List natives directory
+            println!("[MinecraftLauncher] Natives directory: {:?}", natives_dir);
+            if let Ok(entries) = std::fs::read_dir(&natives_dir) {
+                println!("[MinecraftLauncher] Natives found:");
+                for entry in entries.flatten() {
+                    println!("  - {:?}", entry.file_name());
                 }
             } else {
-                // Library without downloads section (Maven style)
-                let maven_url = library.url.as_deref().unwrap_or("https://libraries.minecraft.net/");
-                let path = self.get_relative_library_path(&library.name);
-                let full_url = format!("{}{}", maven_url, path.to_string_lossy().replace("\\", "/"));
-                
-                let lib_path = libraries_dir.join(&path);
-                if !lib_path.exists() {
-                    if let Some(parent) = lib_path.parent() {
-                        std::fs::create_dir_all(parent).ok();
-                    }
-                    println!("[MinecraftLauncher] Downloading library (maven): {}", full_url);
-                    if let Err(e) = self.download_file(&full_url, &lib_path).await {
-                        println!("[MinecraftLauncher] Failed to download library {}: {}", library.name, e);
-                        // If it fails, it might be a local library (generated).
-                        // We should check if it exists locally anyway.
-                    }
-                }
-                if lib_path.exists() {
-                    classpath_entries.push(lib_path.to_string_lossy().to_string());
-                } else {
-                    println!("[MinecraftLauncher] WARNING: Library not found and download failed: {}", library.name);
-                }
-            }
-        }
-        
-        // Debug: List natives directory
-        println!("[MinecraftLauncher] Natives directory: {:?}", natives_dir);
-        if let Ok(entries) = std::fs::read_dir(&natives_dir) {
-            println!("[MinecraftLauncher] Natives found:");
-            for entry in entries.flatten() {
-                println!("  - {:?}", entry.file_name());
+                 println!("[MinecraftLauncher] Warning: Could not read natives directory!");
             }
-        } else {
-             println!("[MinecraftLauncher] Warning: Could not read natives directory!");
-        }
+
+            classpath_entries.extend(resolved.classpath.into_iter().map(|path| ClasspathEntry { path, status: FileStatus::Present }));
+            resolved.natives.into_iter()
+                .map(|native| NativeEntry { path: native.path, status: FileStatus::Present, excludes: native.excludes })
+                .collect()
+        };
 
         // 6. Find Java
         use crate::launcher::java_detector;
-        self.emit_progress("Buscando Java...", 95, 100, 92.0);
-        let java_path = if let Some(custom_java) = options.java_path {
+        self.task.check()?;
+        self.task.progress(LaunchPhase::Java, LaunchPhase::Java.label(), 0, 1);
+        let java_path = if let Some(custom_java) = options.java_path.clone() {
             custom_java
         } else {
             java_detector::find_java()?
@@ -362,18 +513,58 @@ impl MinecraftLauncher {
         println!("[MinecraftLauncher] Using Java: {:?}", java_path);
         
         // 7. Build arguments
-        let (username, uuid, xuid) = match &options.auth {
+        let (username, uuid, xuid) = match &auth {
             AuthData::Microsoft { username, uuid, xuid, .. } => (username.clone(), uuid.clone(), xuid.clone().unwrap_or("0".to_string())),
             AuthData::Offline { username, uuid, xuid } => (username.clone(), uuid.clone(), xuid.clone().unwrap_or("0".to_string())),
+            AuthData::Yggdrasil { username, uuid, .. } => (username.clone(), uuid.clone(), "0".to_string()),
         };
         
-        let access_token = match &options.auth {
+        let mut access_token = match &auth {
             AuthData::Microsoft { access_token, .. } => access_token.clone(),
             AuthData::Offline { .. } => "0".to_string(),
+            AuthData::Yggdrasil { access_token, .. } => access_token.clone(),
+        };
+        let mut user_type = "msa".to_string();
+        let mut user_properties = "{}".to_string();
+
+        // Yggdrasil accounts always need the agent pointed at their auth server
+        let authlib_injector = self.authlib_injector.clone().or_else(|| match &auth {
+            AuthData::Yggdrasil { api_root, .. } => Some(AuthlibInjectorConfig {
+                api_root: api_root.clone(),
+                access_token: None,
+                user_properties: None,
+            }),
+            _ => None,
+        });
+
+        if !prepare && authlib_injector.is_none() && options.local_yggdrasil && matches!(auth, AuthData::Offline { .. }) {
+            pending.push("The embedded Yggdrasil server is started when the game is launched".to_string());
+        }
+
+        let authlib_injector_arg = if let Some(injector) = &authlib_injector {
+            let agent_path = if prepare {
+                self.ensure_authlib_injector(options).await?
+            } else {
+                self.local_authlib_injector(options).unwrap_or_else(Self::authlib_injector_path)
+            };
+            if let Some(token) = &injector.access_token {
+                access_token = token.clone();
+            }
+            if let Some(properties) = &injector.user_properties {
+                user_properties = properties.clone();
+            }
+            user_type = "mojang".to_string();
+            println!("[MinecraftLauncher] Using authlib-injector with {}", injector.api_root);
+            Some(format!("-javaagent:{}={}", agent_path.to_string_lossy(), injector.api_root))
+        } else {
+            None
         };
         
         // Prepare substitutions
-        let classpath = classpath_entries.join(if cfg!(windows) { ";" } else { ":" });
+        let classpath = classpath_entries.iter()
+            .map(|entry| entry.path.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join(if cfg!(windows) { ";" } else { ":" });
         let mut substitutions = std::collections::HashMap::new();
         substitutions.insert("${natives_directory}", natives_dir.to_string_lossy().to_string());
         substitutions.insert("${launcher_name}", "PorcosLauncher".to_string());
@@ -382,7 +573,7 @@ impl MinecraftLauncher {
         substitutions.insert("${library_directory}", libraries_dir.to_string_lossy().to_string());
         substitutions.insert("${classpath_separator}", if cfg!(windows) { ";" } else { ":" }.to_string());
         substitutions.insert("${version_name}", options.version.clone());
-        substitutions.insert("${game_directory}", self.minecraft_dir.to_string_lossy().to_string());
+        substitutions.insert("${game_directory}", self.game_dir.to_string_lossy().to_string());
         substitutions.insert("${assets_root}", assets_dir.to_string_lossy().to_string());
         substitutions.insert("${assets_index_name}", version_details.asset_index.id.clone());
         substitutions.insert("${auth_player_name}", username.clone());
@@ -390,23 +581,29 @@ impl MinecraftLauncher {
         substitutions.insert("${auth_access_token}", access_token.clone());
         substitutions.insert("${auth_xuid}", xuid.clone());
         substitutions.insert("${clientid}", "00000000-0000-0000-0000-000000000000".to_string());
-        substitutions.insert("${user_type}", "msa".to_string());
-        substitutions.insert("${user_properties}", "{}".to_string());
+        substitutions.insert("${user_type}", user_type.clone());
+        substitutions.insert("${user_properties}", user_properties.clone());
         substitutions.insert("${version_type}", version_details.version_type.clone());
-        substitutions.insert("${resolution_width}", "854".to_string());
-        substitutions.insert("${resolution_height}", "480".to_string());
-
-        let mut command = std::process::Command::new(&java_path);
-        
-        #[cfg(target_os = "windows")]
-        {
-            use std::os::windows::process::CommandExt;
-            command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        let (width, height) = options.custom_resolution().unwrap_or((854, 480));
+        substitutions.insert("${resolution_width}", width.to_string());
+        substitutions.insert("${resolution_height}", height.to_string());
+        match &options.quick_play {
+            Some(QuickPlay::Singleplayer { world }) => { substitutions.insert("${quickPlaySingleplayer}", world.clone()); }
+            Some(QuickPlay::Multiplayer { server }) => { substitutions.insert("${quickPlayMultiplayer}", server.clone()); }
+            Some(QuickPlay::Realms { realm }) => { substitutions.insert("${quickPlayRealms}", realm.clone()); }
+            None => {}
         }
 
-        command
-            .arg(format!("-Xmx{}", options.memory_max))
-            .arg(format!("-Xms{}", options.memory_min));
+        let mut jvm_args = vec![
+            format!("-Xmx{}", options.memory_max),
+            format!("-Xms{}", options.memory_min),
+        ];
+
+        // The agent must come before any other JVM argument that may load classes
+        if let Some(agent_arg) = authlib_injector_arg {
+            jvm_args.push(agent_arg);
+            jvm_args.push("-Dauthlibinjector.side=client".to_string());
+        }
 
         // JVM Args
         let mut jvm_args_added = false;
@@ -414,10 +611,7 @@ impl MinecraftLauncher {
             match args {
                 crate::launcher::version_details::Arguments::New(new_args) => {
                     if !new_args.jvm.is_empty() {
-                        let processed = self.get_arguments(&new_args.jvm, &substitutions);
-                        for arg in processed {
-                            command.arg(arg);
-                        }
+                        jvm_args.extend(self.get_arguments(&new_args.jvm, &substitutions, options));
                         jvm_args_added = true;
                     }
                 },
@@ -426,15 +620,38 @@ impl MinecraftLauncher {
         }
 
         if !jvm_args_added {
-            command
-                .arg(format!("-Djava.library.path={}", natives_dir.to_string_lossy()))
-                .arg("-cp")
-                .arg(&classpath);
+            jvm_args.push(format!("-Djava.library.path={}", natives_dir.to_string_lossy()));
+            jvm_args.push("-cp".to_string());
+            jvm_args.push(classpath.clone());
         }
 
-        command.arg(&version_details.main_class);
+        // The XML layout gives the console the level, thread and stack trace of every event
+        let mut logging_config = None;
+        if let Some(logging) = version_details.logging.as_ref().and_then(|logging| logging.client.as_ref()) {
+            let file = &logging.file;
+            let config_path = assets_dir.join("log_configs").join(&file.id);
+            let mut status = integrity::file_status(&config_path, Some(&file.sha1), Some(file.size));
+            if prepare && status != FileStatus::Present {
+                match self.task.run(LaunchPhase::Assets, self.download_verified(&file.url, &config_path, Some(&file.sha1), Some(file.size))).await {
+                    Ok(()) => status = FileStatus::Present,
+                    Err(e @ LauncherError::Cancelled { .. }) => return Err(e),
+                    Err(e) => println!("[MinecraftLauncher] WARNING: Could not download logging config {}: {}", file.id, e),
+                }
+            }
+            // A dry run keeps the argument, the launch downloads the config first
+            if !prepare || status == FileStatus::Present {
+                jvm_args.push(logging.argument.replace("${path}", &config_path.to_string_lossy()));
+            }
+            logging_config = Some(ClasspathEntry { path: config_path, status });
+        }
+
+        let custom = &options.customization;
+        if let Some(extra) = custom.jvm_args.as_deref() {
+            jvm_args.extend(shell_words::split(extra)?);
+        }
         
         // Game Args
+        let mut game_args = Vec::new();
         let mut game_args_added = false;
         let mut has_xuid = false;
         let mut has_client_id = false;
@@ -444,12 +661,12 @@ impl MinecraftLauncher {
              match args {
                 crate::launcher::version_details::Arguments::New(new_args) => {
                     if !new_args.game.is_empty() {
-                        let processed = self.get_arguments(&new_args.game, &substitutions);
+                        let processed = self.get_arguments(&new_args.game, &substitutions, options);
                         for arg in processed {
                             if arg == "--xuid" { has_xuid = true; }
                             if arg == "--clientId" { has_client_id = true; }
                             if arg == "--userProperties" { has_user_properties = true; }
-                            command.arg(arg);
+                            game_args.push(arg);
                         }
                         game_args_added = true;
                     }
@@ -465,27 +682,24 @@ impl MinecraftLauncher {
                      if arg == "--xuid" { has_xuid = true; }
                      if arg == "--clientId" { has_client_id = true; }
                      if arg == "--userProperties" { has_user_properties = true; }
-                     command.arg(arg);
+                     game_args.push(arg.to_string());
                  }
             } else {
                 // Fallback to manual construction if no args found (unlikely for modern versions)
-                let game_args = vec![
+                game_args = vec![
                     "--username".to_string(), username,
                     "--uuid".to_string(), uuid,
-                    "--accessToken".to_string(), access_token,
+                    "--accessToken".to_string(), access_token.clone(),
                     "--xuid".to_string(), xuid.clone(),
                     "--clientId".to_string(), "00000000-0000-0000-0000-000000000000".to_string(),
                     "--version".to_string(), options.version.clone(),
-                    "--gameDir".to_string(), self.minecraft_dir.to_string_lossy().to_string(),
-                    "--assetsDir".to_string(), self.minecraft_dir.join("assets").to_string_lossy().to_string(),
-                    "--assetIndex".to_string(), version_details.asset_index.id,
-                    "--userType".to_string(), "msa".to_string(),
-                    "--versionType".to_string(), version_details.version_type,
-                    "--userProperties".to_string(), "{}".to_string(),
+                    "--gameDir".to_string(), self.game_dir.to_string_lossy().to_string(),
+                    "--assetsDir".to_string(), self.content_dir.join("assets").to_string_lossy().to_string(),
+                    "--assetIndex".to_string(), version_details.asset_index.id.clone(),
+                    "--userType".to_string(), user_type.clone(),
+                    "--versionType".to_string(), version_details.version_type.clone(),
+                    "--userProperties".to_string(), user_properties.clone(),
                 ];
-                for arg in game_args {
-                    command.arg(arg);
-                }
                 has_xuid = true;
                 has_client_id = true;
                 has_user_properties = true;
@@ -493,76 +707,216 @@ impl MinecraftLauncher {
         }
 
         // Force add xuid and clientId if missing and using Microsoft auth
-        if let AuthData::Microsoft { .. } = options.auth {
+        if let AuthData::Microsoft { .. } = auth {
              if !has_xuid && xuid != "0" {
                  println!("[MinecraftLauncher] Appending missing --xuid argument");
-                 command.arg("--xuid");
-                 command.arg(&xuid);
+                 game_args.push("--xuid".to_string());
+                 game_args.push(xuid.clone());
              }
              if !has_client_id {
                  println!("[MinecraftLauncher] Appending missing --clientId argument");
-                 command.arg("--clientId");
-                 command.arg("00000000-0000-0000-0000-000000000000");
+                 game_args.push("--clientId".to_string());
+                 game_args.push("00000000-0000-0000-0000-000000000000".to_string());
              }
              if !has_user_properties {
                  println!("[MinecraftLauncher] Appending missing --userProperties argument");
-                 command.arg("--userProperties");
-                 command.arg("{}");
+                 game_args.push("--userProperties".to_string());
+                 game_args.push(user_properties.clone());
              }
         }
+
+        // Versions without feature rules (pre-1.13) only take these as plain flags
+        let has_arg = |args: &[String], name: &str| args.iter().any(|arg| arg == name);
+        if options.demo && !has_arg(&game_args, "--demo") {
+            game_args.push("--demo".to_string());
+        }
+        if let Some((width, height)) = options.custom_resolution() {
+            if !has_arg(&game_args, "--width") {
+                game_args.extend(["--width".to_string(), width.to_string(), "--height".to_string(), height.to_string()]);
+            }
+        }
+        if options.fullscreen && !has_arg(&game_args, "--fullscreen") {
+            game_args.push("--fullscreen".to_string());
+        }
+
+        // Before 1.20 there is no quick play, but servers can still be joined with --server/--port
+        match &options.quick_play {
+            Some(QuickPlay::Multiplayer { server }) if !has_arg(&game_args, "--quickPlayMultiplayer") => {
+                let (host, port) = split_server_address(server);
+                println!("[MinecraftLauncher] Quick play not supported by {}, joining {}:{} with --server", options.version, host, port);
+                game_args.extend(["--server".to_string(), host, "--port".to_string(), port]);
+            }
+            Some(quick_play) if !game_args.iter().any(|arg| arg.starts_with("--quickPlay")) => {
+                println!("[MinecraftLauncher] WARNING: {} has no quick play support, ignoring {:?}", options.version, quick_play);
+            }
+            _ => {}
+        }
+        
+        if let Some(extra) = custom.game_args.as_deref() {
+            game_args.extend(shell_words::split(extra)?);
+        }
+        let wrapper = match custom.wrapper_command.as_deref() {
+            Some(wrapper) => shell_words::split(wrapper)?,
+            None => Vec::new(),
+        };
+
+        // Offline accounts use "0" as a placeholder token
+        let secrets = if access_token.len() > 1 { vec![access_token] } else { Vec::new() };
+
+        Ok(LaunchPlan {
+            version: options.version.clone(),
+            java_path,
+            // Working directory is the game directory
+            working_dir: self.game_dir.clone(),
+            jvm_args,
+            main_class: version_details.main_class,
+            game_args,
+            wrapper,
+            env: custom.env_vars.iter().map(|(key, value)| (key.clone(), value.clone())).collect::<BTreeMap<_, _>>(),
+            classpath: classpath_entries,
+            natives_dir,
+            natives: native_entries,
+            logging_config,
+            pending,
+            secrets,
+        })
+    }
+
+    pub async fn launch(&self, options: LaunchOptions) -> LauncherResult<LaunchResult> {
+        println!("[MinecraftLauncher] ========== LAUNCHING MINECRAFT ==========");
+        let instance_id = self.instance_id(&options);
+        // Held until the game is registered; an error or a cancelled launch drops it
+        let reservation = self.processes.reserve(&instance_id, options.force)?;
+        let plan = self.plan(&options, true).await?;
+        let mut command = plan.command();
         
-        // Set working directory to game directory
-        command.current_dir(&self.minecraft_dir);
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+
+        let custom = &options.customization;
+        if let Some(hook) = &custom.pre_launch_command {
+            println!("[MinecraftLauncher] Running pre-launch command: {}", hook);
+            let mut hook_command = tokio::process::Command::from(hook_command(hook, &plan));
+            hook_command.kill_on_drop(true);
+            let result = self.task.cancellable(async {
+                hook_command.status().await.map_err(|e| LauncherError::io(format!("Failed to run pre-launch command: {}", e)))
+            }).await;
+            let failure = match result {
+                Ok(status) if status.success() => None,
+                Ok(status) => Some(format!("Pre-launch command exited with {}", status)),
+                Err(e @ LauncherError::Cancelled { .. }) => return Err(e),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(failure) = failure {
+                if custom.abort_on_hook_failure {
+                    return Err(LauncherError::Other(failure));
+                }
+                println!("[MinecraftLauncher] WARNING: {}, launching anyway", failure);
+            }
+        }
+        let post_exit_command = custom.post_exit_command.as_deref().map(|hook| hook_command(hook, &plan));
 
         println!("[MinecraftLauncher] Launching game process...");
-        println!("[MinecraftLauncher] Command: {:?}", command);
-        self.emit_progress("Iniciando proceso del juego...", 98, 100, 95.0);
+        println!("[MinecraftLauncher] Command: {:?}", plan.redacted().command());
+        // Last point where the launch can be cancelled; after this the game is running
+        self.task.check()?;
+        self.task.progress(LaunchPhase::Spawn, LaunchPhase::Spawn.label(), 0, 1);
         
         // 8. Launch the game!
-        // Configure stdout to be piped
+        // Both pipes are captured: JVM startup failures only show up on stderr
         command.stdout(std::process::Stdio::piped());
+        command.stderr(std::process::Stdio::piped());
+        let log = SessionLog::create(&self.game_dir, &instance_id, &plan);
+        if let Some(path) = log.path() {
+            println!("[MinecraftLauncher] Session log: {:?}", path);
+        }
         
-        let mut child = command
-            .spawn()
-            .map_err(|e| format!("Failed to spawn Minecraft process: {}", e))?;
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                log.note(&format!("Failed to spawn the game process: {}", e));
+                return Err(LauncherError::java(format!("Failed to spawn Minecraft process: {}", e)));
+            }
+        };
         
         let process_id = child.id();
         println!("[MinecraftLauncher] ✅ Minecraft launched! PID: {}", process_id);
+        log.note(&format!("Game started with PID {}", process_id));
+        self.console.start_session(&instance_id, process_id);
+        let output = GameOutput::new(instance_id.clone(), log, self.console.clone(), self.window.clone());
         
+        let stdout = child.stdout.take();
+        let stderr_thread = child.stderr.take().map(|stderr| {
+            let output = output.clone();
+            std::thread::spawn(move || {
+                use std::io::{BufRead, BufReader};
+                for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                    output.line(OutputStream::Stderr, &line);
+                }
+            })
+        });
+        let child = reservation.register(RunningGame {
+            instance_id: instance_id.clone(),
+            pid: process_id,
+            version: options.version.clone(),
+            game_dir: self.game_dir.clone(),
+            started_at: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0),
+        }, child);
+
         // Spawn a thread to monitor stdout
-        if let Some(stdout) = child.stdout.take() {
+        if let Some(stdout) = stdout {
             let window_clone = self.window.clone();
-            let minecraft_dir = self.minecraft_dir.clone();
+            let processes = self.processes.clone();
+            let task = self.task.clone();
+            let game_dir = self.game_dir.clone();
+            let mut post_exit_command = post_exit_command;
             
             std::thread::spawn(move || {
                 use std::io::{BufRead, BufReader};
                 let reader = BufReader::new(stdout);
                 let mut game_started = false;
+                let mut parser = Log4jParser::default();
                 
                 for line in reader.lines() {
                     if let Ok(line) = line {
-                        // Emit game output event (optional, for console)
-                        if let Some(window) = &window_clone {
-                            let _ = window.emit("game-output", line.clone());
-                            
-                            // Check for Render thread (Game Ready)
-                            if !game_started && (line.contains("[Render thread/INFO]:") || line.contains("Sound engine started")) {
+                        for parsed in parser.feed(&line) {
+                            // Session log, console history and the game-output event
+                            let ready = match parsed {
+                                LogLine::Record(record) => {
+                                    output.record(&record);
+                                    record.is_ready_marker()
+                                }
+                                LogLine::Plain(text) => {
+                                    output.line(OutputStream::Stdout, &text);
+                                    // Plain text layout (no logging config in the version JSON)
+                                    text.contains("[Render thread/INFO]:") || text.contains("Sound engine started")
+                                }
+                            };
+
+                            // Game Ready
+                            if ready && !game_started {
                                 game_started = true;
-                                let event = ProgressEvent {
-                                    stage: "¡Juego iniciado!".to_string(),
-                                    progress: 100.0,
-                                    total: 100,
-                                    current: 100,
-                                };
-                                let _ = window.emit("launch-progress", event);
+                                task.complete("¡Juego iniciado!");
                             }
                         }
-                        println!("[Game] {}", line);
                     }
                 }
 
                 // Wait for process to exit and check for crash
-                match child.wait() {
+                let exit = process_registry::wait(&child);
+                // Let the last stderr lines through before reporting the exit
+                if let Some(stderr_thread) = stderr_thread {
+                    let _ = stderr_thread.join();
+                }
+                match &exit {
+                    Ok(status) => output.log().note(&format!("Game exited with {}", status)),
+                    Err(e) => output.log().note(&format!("Failed to wait for the game process: {}", e)),
+                }
+                processes.unregister(&instance_id, process_id);
+                match &exit {
                     Ok(status) => {
                         println!("[MinecraftLauncher] Process finished. Exit code: {:?}", status.code());
                         
@@ -570,7 +924,7 @@ impl MinecraftLauncher {
                             println!("[MinecraftLauncher] Game exited with error. Checking for crash reports...");
                             
                             // Look for crash reports
-                            let crash_reports_dir = minecraft_dir.join("crash-reports");
+                            let crash_reports_dir = game_dir.join("crash-reports");
                             println!("[MinecraftLauncher] Checking directory: {:?}", crash_reports_dir);
                             
                             if crash_reports_dir.exists() {
@@ -609,6 +963,7 @@ impl MinecraftLauncher {
                                                         // It's likely the crash report for this session
                                                         if let Ok(content) = std::fs::read_to_string(&report_path) {
                                                             let msg = format!("[Game] #@!@# Game crashed! Crash report saved to: #@!@# {}", report_path.to_string_lossy());
+                                                            output.log().note(&format!("Crash report: {}", report_path.to_string_lossy()));
                                                             if let Some(window) = &window_clone {
                                                                 let _ = window.emit("game-output", msg.clone());
                                                                 // let _ = window.emit("game-output", content.clone()); // Don't spam console with full report
@@ -660,6 +1015,16 @@ impl MinecraftLauncher {
                         }
                     },
                 }
+
+                if let Some(hook) = post_exit_command.as_mut() {
+                    let exit_code = exit.ok().and_then(|status| status.code());
+                    hook.env("MC_EXIT_CODE", exit_code.map(|code| code.to_string()).unwrap_or_default());
+                    match hook.status() {
+                        Ok(status) if status.success() => {}
+                        Ok(status) => println!("[MinecraftLauncher] WARNING: Post-exit command exited with {}", status),
+                        Err(e) => println!("[MinecraftLauncher] WARNING: Failed to run post-exit command: {}", e),
+                    }
+                }
             });
         }
         
@@ -670,25 +1035,25 @@ impl MinecraftLauncher {
         })
     }
 
-    async fn get_fabric_profile(&self, game_version: &str, loader_version: &str) -> Result<LoaderManifest, String> {
+    async fn get_fabric_profile(&self, game_version: &str, loader_version: &str) -> LauncherResult<LoaderManifest> {
         let url = format!("https://meta.fabricmc.net/v2/versions/loader/{}/{}/profile/json", game_version, loader_version);
-        let response = self.http_client.get(&url).send().await.map_err(|e| e.to_string())?;
+        let response = self.http_client.get(&url).send().await?;
         if !response.status().is_success() {
-            return Err(format!("Failed to fetch Fabric profile: {}", response.status()));
+            return Err(LauncherError::http_status(&url, response.status().as_u16()));
         }
-        response.json().await.map_err(|e| e.to_string())
+        response.json().await.map_err(LauncherError::from)
     }
 
-    async fn get_quilt_profile(&self, game_version: &str, loader_version: &str) -> Result<LoaderManifest, String> {
+    async fn get_quilt_profile(&self, game_version: &str, loader_version: &str) -> LauncherResult<LoaderManifest> {
         let url = format!("https://meta.quiltmc.org/v3/versions/loader/{}/{}/profile/json", game_version, loader_version);
-        let response = self.http_client.get(&url).send().await.map_err(|e| e.to_string())?;
+        let response = self.http_client.get(&url).send().await?;
         if !response.status().is_success() {
-            return Err(format!("Failed to fetch Quilt profile: {}", response.status()));
+            return Err(LauncherError::http_status(&url, response.status().as_u16()));
         }
-        response.json().await.map_err(|e| e.to_string())
+        response.json().await.map_err(LauncherError::from)
     }
 
-    async fn get_neoforge_profile(&self, _game_version: &str, loader_version: &str) -> Result<LoaderManifest, String> {
+    async fn get_neoforge_profile(&self, _game_version: &str, loader_version: &str) -> LauncherResult<LoaderManifest> {
         // Construct installer URL
         let url = if loader_version.starts_with("1.20.1") {
              format!("https://maven.neoforged.net/releases/net/neoforged/forge/{}/forge-{}-installer.jar", loader_version, loader_version)
@@ -707,59 +1072,59 @@ impl MinecraftLauncher {
         self.install_neoforge_client(&installer_path).await?;
         
         // Extract client.json
-        let file = std::fs::File::open(&installer_path).map_err(|e| e.to_string())?;
-        let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+        let file = std::fs::File::open(&installer_path)?;
+        let mut archive = zip::ZipArchive::new(file)?;
         
         // Try "client.json" or "version.json"
         let mut content = String::new();
         let mut found = false;
 
         if let Ok(mut file) = archive.by_name("client.json") {
-            std::io::Read::read_to_string(&mut file, &mut content).map_err(|e| e.to_string())?;
+            std::io::Read::read_to_string(&mut file, &mut content)?;
             found = true;
         }
         
         if !found {
             if let Ok(mut file) = archive.by_name("version.json") {
-                std::io::Read::read_to_string(&mut file, &mut content).map_err(|e| e.to_string())?;
+                std::io::Read::read_to_string(&mut file, &mut content)?;
                 found = true;
             }
         }
 
         if !found {
-            return Err("Could not find client.json or version.json in NeoForge installer".to_string());
+            return Err(LauncherError::loader("neoforge", "Could not find client.json or version.json in NeoForge installer"));
         }
         
         // Parse
-        let manifest: LoaderManifest = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        let manifest: LoaderManifest = serde_json::from_str(&content)?;
         
         Ok(manifest)
     }
 
-    async fn install_neoforge_client(&self, installer_path: &PathBuf) -> Result<(), String> {
+    async fn install_neoforge_client(&self, installer_path: &PathBuf) -> LauncherResult<()> {
         println!("[MinecraftLauncher] Installing NeoForge client from {:?}", installer_path);
         
         // 1. Determine package prefix by inspecting the jar
-        let file = std::fs::File::open(installer_path).map_err(|e| e.to_string())?;
-        let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+        let file = std::fs::File::open(installer_path)?;
+        let mut archive = zip::ZipArchive::new(file)?;
         
         let package_prefix = if archive.by_name("net/neoforged/installer/SimpleInstaller.class").is_ok() {
             "net.neoforged"
         } else if archive.by_name("net/minecraftforge/installer/SimpleInstaller.class").is_ok() {
             "net.minecraftforge"
         } else {
-            return Err("Unknown NeoForge installer structure (neither net.neoforged nor net.minecraftforge found)".to_string());
+            return Err(LauncherError::loader("neoforge", "Unknown NeoForge installer structure (neither net.neoforged nor net.minecraftforge found)"));
         };
         
         println!("[MinecraftLauncher] Detected NeoForge installer package: {}", package_prefix);
 
         // 2. Create temp directory for installation
         let install_dir = std::env::temp_dir().join(format!("neoforge_install_{}", uuid::Uuid::new_v4()));
-        std::fs::create_dir_all(&install_dir).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&install_dir)?;
         
         // Create dummy launcher profiles to trick the installer
-        std::fs::write(install_dir.join("launcher_profiles.json"), "{}").map_err(|e| e.to_string())?;
-        std::fs::write(install_dir.join("launcher_profiles_microsoft_store.json"), "{}").map_err(|e| e.to_string())?;
+        std::fs::write(install_dir.join("launcher_profiles.json"), "{}")?;
+        std::fs::write(install_dir.join("launcher_profiles_microsoft_store.json"), "{}")?;
 
         // 3. Write the installer script
         let script_content = format!(r#"
@@ -796,7 +1161,7 @@ public class NeoForgeInstaller {{
 "#, package_prefix);
 
         let script_path = install_dir.join("NeoForgeInstaller.java");
-        std::fs::write(&script_path, script_content).map_err(|e| e.to_string())?;
+        std::fs::write(&script_path, script_content)?;
         
         // 4. Run the installer script
         // We need to find java again.
@@ -804,25 +1169,28 @@ public class NeoForgeInstaller {{
         let java_path = java_detector::find_java()?;
         
         println!("[MinecraftLauncher] Running NeoForge installer script...");
-        let output = std::process::Command::new(&java_path)
+        let output = tokio::process::Command::new(&java_path)
             .arg("-cp")
             .arg(installer_path) // Classpath: just the installer jar
             .arg(&script_path)   // Source file to run
             .current_dir(&install_dir) // Run in temp dir so libraries are generated there
+            // Cancelling the launch drops this future, which must also stop the installer
+            .kill_on_drop(true)
             .output()
-            .map_err(|e| format!("Failed to execute Java: {}", e))?;
+            .await
+            .map_err(|e| LauncherError::java(format!("Failed to execute Java: {}", e)))?;
             
         if !output.status.success() {
             let stdout = String::from_utf8_lossy(&output.stdout);
             let stderr = String::from_utf8_lossy(&output.stderr);
             println!("[MinecraftLauncher] Installer STDOUT: {}", stdout);
             println!("[MinecraftLauncher] Installer STDERR: {}", stderr);
-            return Err(format!("NeoForge installer failed with exit code {}", output.status));
+            return Err(LauncherError::loader("neoforge", format!("NeoForge installer failed with exit code {}", output.status)));
         }
         
         // 5. Copy generated libraries to the real libraries directory
         let generated_libs = install_dir.join("libraries");
-        let target_libs = self.minecraft_dir.join("libraries");
+        let target_libs = self.content_dir.join("libraries");
         
         if generated_libs.exists() {
             println!("[MinecraftLauncher] Copying generated libraries to {:?}", target_libs);
@@ -837,24 +1205,24 @@ public class NeoForgeInstaller {{
         Ok(())
     }
 
-    fn copy_dir_all(&self, src: &PathBuf, dst: &PathBuf) -> Result<(), String> {
-        std::fs::create_dir_all(dst).map_err(|e| e.to_string())?;
-        for entry in std::fs::read_dir(src).map_err(|e| e.to_string())? {
-            let entry = entry.map_err(|e| e.to_string())?;
-            let ty = entry.file_type().map_err(|e| e.to_string())?;
+    fn copy_dir_all(&self, src: &PathBuf, dst: &PathBuf) -> LauncherResult<()> {
+        std::fs::create_dir_all(dst)?;
+        for entry in std::fs::read_dir(src)? {
+            let entry = entry?;
+            let ty = entry.file_type()?;
             let src_path = entry.path();
             let dst_path = dst.join(entry.file_name());
             
             if ty.is_dir() {
                 self.copy_dir_all(&src_path, &dst_path)?;
             } else {
-                std::fs::copy(&src_path, &dst_path).map_err(|e| e.to_string())?;
+                std::fs::copy(&src_path, &dst_path)?;
             }
         }
         Ok(())
     }
 
-    async fn get_forge_profile(&self, game_version: &str, loader_version: &str) -> Result<LoaderManifest, String> {
+    async fn get_forge_profile(&self, game_version: &str, loader_version: &str) -> LauncherResult<LoaderManifest> {
         // URL format: https://maven.minecraftforge.net/net/minecraftforge/forge/{mc_ver}-{forge_ver}/forge-{mc_ver}-{forge_ver}-installer.jar
         let long_version = format!("{}-{}", game_version, loader_version);
         let url = format!("https://maven.minecraftforge.net/net/minecraftforge/forge/{}/forge-{}-installer.jar", long_version, long_version);
@@ -868,41 +1236,41 @@ public class NeoForgeInstaller {{
         // Run installer
         self.install_forge_client(&installer_path).await?;
 
-        let file = std::fs::File::open(&installer_path).map_err(|e| e.to_string())?;
-        let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+        let file = std::fs::File::open(&installer_path)?;
+        let mut archive = zip::ZipArchive::new(file)?;
         
         let mut content = String::new();
         let mut found = false;
 
         if let Ok(mut file) = archive.by_name("version.json") {
-            std::io::Read::read_to_string(&mut file, &mut content).map_err(|e| e.to_string())?;
+            std::io::Read::read_to_string(&mut file, &mut content)?;
             found = true;
         }
         
         if !found {
             if let Ok(mut file) = archive.by_name("install_profile.json") {
-                std::io::Read::read_to_string(&mut file, &mut content).map_err(|e| e.to_string())?;
+                std::io::Read::read_to_string(&mut file, &mut content)?;
                 found = true;
             }
         }
 
         if !found {
-            return Err("Could not find version.json in Forge installer".to_string());
+            return Err(LauncherError::loader("forge", "Could not find version.json in Forge installer"));
         }
         
-        let manifest: LoaderManifest = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        let manifest: LoaderManifest = serde_json::from_str(&content)?;
         Ok(manifest)
     }
 
-    async fn install_forge_client(&self, installer_path: &PathBuf) -> Result<(), String> {
+    async fn install_forge_client(&self, installer_path: &PathBuf) -> LauncherResult<()> {
         println!("[MinecraftLauncher] Installing Forge client from {:?}", installer_path);
         
         let install_dir = std::env::temp_dir().join(format!("forge_install_{}", uuid::Uuid::new_v4()));
-        std::fs::create_dir_all(&install_dir).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&install_dir)?;
         
         // Dummy profiles
-        std::fs::write(install_dir.join("launcher_profiles.json"), "{}").map_err(|e| e.to_string())?;
-        std::fs::write(install_dir.join("launcher_profiles_microsoft_store.json"), "{}").map_err(|e| e.to_string())?;
+        std::fs::write(install_dir.join("launcher_profiles.json"), "{}")?;
+        std::fs::write(install_dir.join("launcher_profiles_microsoft_store.json"), "{}")?;
 
         let script_content = r#"
 import java.io.File;
@@ -937,30 +1305,33 @@ public class ForgeInstaller {
 }
 "#;
         let script_path = install_dir.join("ForgeInstaller.java");
-        std::fs::write(&script_path, script_content).map_err(|e| e.to_string())?;
+        std::fs::write(&script_path, script_content)?;
         
         use crate::launcher::java_detector;
         let java_path = java_detector::find_java()?;
         
         println!("[MinecraftLauncher] Running Forge installer script...");
-        let output = std::process::Command::new(&java_path)
+        let output = tokio::process::Command::new(&java_path)
             .arg("-cp")
             .arg(installer_path)
             .arg(&script_path)
             .current_dir(&install_dir)
+            // Cancelling the launch drops this future, which must also stop the installer
+            .kill_on_drop(true)
             .output()
-            .map_err(|e| format!("Failed to execute Java: {}", e))?;
+            .await
+            .map_err(|e| LauncherError::java(format!("Failed to execute Java: {}", e)))?;
             
         if !output.status.success() {
             let stdout = String::from_utf8_lossy(&output.stdout);
             let stderr = String::from_utf8_lossy(&output.stderr);
             println!("[MinecraftLauncher] Installer STDOUT: {}", stdout);
             println!("[MinecraftLauncher] Installer STDERR: {}", stderr);
-            return Err(format!("Forge installer failed with exit code {}", output.status));
+            return Err(LauncherError::loader("forge", format!("Forge installer failed with exit code {}", output.status)));
         }
         
         let generated_libs = install_dir.join("libraries");
-        let target_libs = self.minecraft_dir.join("libraries");
+        let target_libs = self.content_dir.join("libraries");
         
         if generated_libs.exists() {
             println!("[MinecraftLauncher] Copying generated libraries to {:?}", target_libs);
@@ -971,29 +1342,17 @@ public class ForgeInstaller {
         Ok(())
     }
 
-    fn get_relative_library_path(&self, library_name: &str) -> PathBuf {
-        let parts: Vec<&str> = library_name.split(':').collect();
-        if parts.len() >= 3 {
-            let group = parts[0].replace('.', "/");
-            let artifact = parts[1];
-            let version = parts[2];
-            PathBuf::from(group).join(artifact).join(version).join(format!("{}-{}.jar", artifact, version))
-        } else {
-            PathBuf::from(format!("{}.jar", library_name))
-        }
-    }
-
-    fn extract_native(&self, jar_path: &std::path::Path, output_dir: &std::path::Path, excludes: &[String]) -> Result<(), String> {
+    fn extract_native(&self, jar_path: &std::path::Path, output_dir: &std::path::Path, excludes: &[String]) -> LauncherResult<()> {
         println!("[MinecraftLauncher] Extracting native jar: {:?}", jar_path);
         let file = std::fs::File::open(jar_path)
-            .map_err(|e| format!("Failed to open native jar: {}", e))?;
+            .map_err(|e| LauncherError::io(format!("Failed to open native jar: {}", e)))?;
         
         let mut archive = zip::ZipArchive::new(file)
-            .map_err(|e| format!("Failed to read zip archive: {}", e))?;
+            .map_err(|e| LauncherError::io(format!("Failed to read zip archive: {}", e)))?;
             
         for i in 0..archive.len() {
             let mut file = archive.by_index(i)
-                .map_err(|e| format!("Failed to read zip file: {}", e))?;
+                .map_err(|e| LauncherError::io(format!("Failed to read zip file: {}", e)))?;
                 
             // Get the full path in the zip
             let full_path = match file.enclosed_name() {
@@ -1035,60 +1394,157 @@ public class ForgeInstaller {
             // Create parent directories if needed
             if let Some(parent) = outpath.parent() {
                 std::fs::create_dir_all(parent)
-                    .map_err(|e| format!("Failed to create parent dir {:?}: {}", parent, e))?;
+                    .map_err(|e| LauncherError::io(format!("Failed to create parent dir {:?}: {}", parent, e)))?;
             }
             
             // Create output file
             let mut outfile = std::fs::File::create(&outpath)
-                .map_err(|e| format!("Failed to create output file {:?}: {}", outpath, e))?;
+                .map_err(|e| LauncherError::io(format!("Failed to create output file {:?}: {}", outpath, e)))?;
             std::io::copy(&mut file, &mut outfile)
-                .map_err(|e| format!("Failed to copy file content: {}", e))?;
+                .map_err(|e| LauncherError::io(format!("Failed to copy file content: {}", e)))?;
         }
         
         Ok(())
     }
 
-    fn get_library_path(&self, library_name: &str, libraries_dir: &std::path::Path) -> std::path::PathBuf {
-        // Parse Maven-style library name: group:artifact:version
-        let parts: Vec<&str> = library_name.split(':').collect();
-        if parts.len() >= 3 {
-            let group = parts[0].replace('.', "/");
-            let artifact = parts[1];
-            let version = parts[2];
-            
-            libraries_dir
-                .join(group)
-                .join(artifact)
-                .join(version)
-                .join(format!("{}-{}.jar", artifact, version))
-        } else {
-            libraries_dir.join(format!("{}.jar", library_name))
-        }
+    async fn download_file(&self, url: &str, path: &std::path::Path) -> LauncherResult<()> {
+        println!("[MinecraftLauncher] Downloading file: {}", url);
+        self.downloads.fetch(DownloadRequest::new(url, path).priority(Priority::High).group(self.task.id())).await
     }
 
-    async fn download_file(&self, url: &str, path: &std::path::Path) -> Result<(), String> {
+    /// Download a file and check it against the expected hash and size
+    async fn download_verified(&self, url: &str, path: &std::path::Path, sha1: Option<&str>, size: Option<u64>) -> LauncherResult<()> {
         println!("[MinecraftLauncher] Downloading file: {}", url);
-        let response = self.http_client.get(url)
+        let request = DownloadRequest::new(url, path)
+            .checksum(sha1.map(str::to_string), size)
+            .priority(Priority::High)
+            .group(self.task.id());
+        self.downloads.fetch(request).await
+    }
+
+    /// Check an installed version's client jar, libraries and assets without launching it,
+    /// downloading again anything that is missing or doesn't match its checksum
+    pub async fn verify_and_repair(&self, version: &str) -> LauncherResult<VerifyReport> {
+        println!("[MinecraftLauncher] Verifying version {}...", version);
+        let version_manager = crate::launcher::VersionManager::new(self.content_dir.join("versions"));
+        let details = version_manager.get_version_details(version).await?;
+
+        let client_jar_path = self.content_dir.join("versions").join(version).join(format!("{}.jar", version));
+        let client = &details.downloads.client;
+        let mut report = VerifyReport { checked: 1, ..Default::default() };
+        self.task.progress(LaunchPhase::Client, &format!("Verificando {}.jar...", version), 0, 1);
+        if !integrity::is_valid(&client_jar_path, Some(&client.sha1), Some(client.size)) {
+            println!("[MinecraftLauncher] Repairing {}.jar", version);
+            match self.download_verified(&client.url, &client_jar_path, Some(&client.sha1), Some(client.size)).await {
+                Ok(()) => report.repaired.push(format!("{}.jar", version)),
+                Err(e) => {
+                    println!("[MinecraftLauncher] Failed to repair {}.jar: {}", version, e);
+                    report.failed.push(format!("{}.jar", version));
+                }
+            }
+        }
+
+        let library_manager = LibraryManager::new(
+            self.content_dir.join("libraries"),
+            self.content_dir.join("natives"),
+            self.task.clone(),
+            self.downloads.clone(),
+        );
+        match library_manager.resolve(&details.libraries).await {
+            Ok(resolved) => report.merge(resolved.report),
+            Err(e) => {
+                println!("[MinecraftLauncher] Failed to repair libraries: {}", e);
+                report.failed.push(format!("libraries: {}", e));
+            }
+        }
+
+        use crate::launcher::asset_manager::AssetManager;
+        let asset_manager = AssetManager::new(self.content_dir.join("assets"), self.task.clone(), self.downloads.clone());
+        report.merge(asset_manager.download_assets(&details.asset_index).await?);
+
+        println!("[MinecraftLauncher] Verified {} files: {} repaired, {} failed", report.checked, report.repaired.len(), report.failed.len());
+        self.task.complete("Verificación completa");
+        Ok(report)
+    }
+
+    /// Downloaded authlib-injector agent, in the launcher data directory
+    fn authlib_injector_path() -> PathBuf {
+        Self::get_launcher_data_dir().join("authlib-injector.jar")
+    }
+
+    /// Metadata of the downloaded agent; it is only reused while it matches the pinned build
+    fn authlib_injector_meta_path() -> PathBuf {
+        Self::get_launcher_data_dir().join("authlib-injector.json")
+    }
+
+    /// An agent that needs no network: the one given in the launch options, then one shipped
+    /// as an `authlib-injector.jar` app resource, then a verified earlier download
+    fn local_authlib_injector(&self, options: &LaunchOptions) -> Option<PathBuf> {
+        if let Some(path) = options.authlib_injector_path.clone().filter(|path| path.is_file()) {
+            return Some(path);
+        }
+
+        let bundled = self.window.as_ref()
+            .and_then(|window| window.path().resource_dir().ok())
+            .map(|dir| dir.join("authlib-injector.jar"))
+            .filter(|path| path.is_file());
+        if bundled.is_some() {
+            return bundled;
+        }
+
+        let agent_path = Self::authlib_injector_path();
+        let meta: AuthlibInjectorArtifact = std::fs::read_to_string(Self::authlib_injector_meta_path()).ok()
+            .and_then(|content| serde_json::from_str(&content).ok())?;
+        let matches = meta.build_number == AUTHLIB_INJECTOR_BUILD
+            && integrity::sha256_file(&agent_path).is_ok_and(|hash| hash.eq_ignore_ascii_case(&meta.checksums.sha256));
+        matches.then_some(agent_path)
+    }
+
+    /// Resolve the agent, downloading the pinned build when there is no local copy.
+    /// The jar runs inside the game, so the download must match the published SHA-256.
+    async fn ensure_authlib_injector(&self, options: &LaunchOptions) -> LauncherResult<PathBuf> {
+        if let Some(path) = self.local_authlib_injector(options) {
+            return Ok(path);
+        }
+        if let Some(path) = &options.authlib_injector_path {
+            return Err(LauncherError::io_at(path, "The configured authlib-injector jar does not exist"));
+        }
+
+        println!("[MinecraftLauncher] Fetching authlib-injector build {}...", AUTHLIB_INJECTOR_BUILD);
+        let metadata_url = format!("https://authlib-injector.yushi.moe/artifact/{}.json", AUTHLIB_INJECTOR_BUILD);
+        let response = self.http_client.get(&metadata_url)
             .send()
             .await
-            .map_err(|e| format!("Download failed (network): {}", e))?;
-        
+            .map_err(|e| LauncherError::network(format!("Failed to fetch authlib-injector metadata: {}", e)))?;
         if !response.status().is_success() {
-            return Err(format!("Download failed with status: {} for URL: {}", response.status(), url));
+            return Err(LauncherError::http_status(&metadata_url, response.status().as_u16()));
         }
-        
-        let bytes = response.bytes()
+        let artifact: AuthlibInjectorArtifact = response.json()
             .await
-            .map_err(|e| format!("Failed to read response: {}", e))?;
-        
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory: {}", e))?;
+            .map_err(|e| LauncherError::parse(format!("Failed to parse authlib-injector metadata: {}", e)))?;
+        if artifact.build_number != AUTHLIB_INJECTOR_BUILD {
+            return Err(LauncherError::parse(format!("Expected authlib-injector build {}, got {}", AUTHLIB_INJECTOR_BUILD, artifact.build_number)));
         }
 
-        std::fs::write(path, &bytes)
-            .map_err(|e| format!("Failed to write file: {}", e))?;
-        
-        Ok(())
+        let agent_path = Self::authlib_injector_path();
+        let download_path = agent_path.with_extension("jar.part");
+        self.download_file(&artifact.download_url, &download_path).await?;
+
+        let actual = integrity::sha256_file(&download_path)
+            .map_err(|e| LauncherError::io_at(&download_path, format!("Failed to hash authlib-injector: {}", e)))?;
+        if !actual.eq_ignore_ascii_case(&artifact.checksums.sha256) {
+            let _ = std::fs::remove_file(&download_path);
+            return Err(LauncherError::Checksum { path: agent_path, expected: artifact.checksums.sha256, actual });
+        }
+
+        std::fs::rename(&download_path, &agent_path)
+            .map_err(|e| LauncherError::io_at(&agent_path, format!("Failed to store authlib-injector: {}", e)))?;
+        let meta = serde_json::to_string_pretty(&artifact)?;
+        std::fs::write(Self::authlib_injector_meta_path(), meta)
+            .map_err(|e| LauncherError::io(format!("Failed to store authlib-injector metadata: {}", e)))?;
+
+        println!("[MinecraftLauncher] authlib-injector {} verified", artifact.version);
+        Ok(agent_path)
     }
 
     /// Generate offline UUID from username (same algorithm as old launcher)
@@ -1138,7 +1594,21 @@ public class ForgeInstaller {
         }
     }
 
-    fn get_arguments(&self, args: &Vec<crate::launcher::version_details::ArgumentItem>, substitutions: &std::collections::HashMap<&str, String>) -> Vec<String> {
+    /// Shared store for versions, libraries, assets and native jars (`.porcos/content`).
+    /// It belongs to this launcher; the official launcher's directory is never written to.
+    pub fn get_shared_content_dir() -> PathBuf {
+        Self::get_launcher_data_dir().join("content")
+    }
+
+    /// Get the launcher's own data directory (`.porcos`, next to the Minecraft directory)
+    pub fn get_launcher_data_dir() -> PathBuf {
+        let mut path = Self::get_default_minecraft_dir();
+        path.pop(); // Go up from .minecraft
+        path.push(".porcos");
+        path
+    }
+
+    fn get_arguments(&self, args: &Vec<crate::launcher::version_details::ArgumentItem>, substitutions: &std::collections::HashMap<&str, String>, options: &LaunchOptions) -> Vec<String> {
         let mut result = Vec::new();
         for arg in args {
             match arg {
@@ -1146,7 +1616,7 @@ public class ForgeInstaller {
                     result.push(self.replace_variables(value, substitutions));
                 },
                 crate::launcher::version_details::ArgumentItem::Conditional(cond) => {
-                    if self.check_rules(&cond.rules) {
+                    if self.check_rules(&cond.rules, options) {
                         match &cond.value {
                             crate::launcher::version_details::ArgumentValue::Single(v) => {
                                 result.push(self.replace_variables(v, substitutions));
@@ -1172,7 +1642,7 @@ public class ForgeInstaller {
         result
     }
 
-    fn check_rules(&self, rules: &Vec<crate::launcher::version_details::Rule>) -> bool {
+    fn check_rules(&self, rules: &Vec<crate::launcher::version_details::Rule>, options: &LaunchOptions) -> bool {
         for rule in rules {
             let mut os_match = true;
             let mut arch_match = true;
@@ -1199,10 +1669,8 @@ public class ForgeInstaller {
             }
 
             if let Some(features) = &rule.features {
-                for (_feature, value) in features {
-                    // We currently don't support any specific features like quick_play
-                    // So if a feature is required (true), we fail the match.
-                    if *value {
+                for (feature, value) in features {
+                    if options.has_feature(feature) != *value {
                         feature_match = false;
                     }
                 }
@@ -1249,3 +1717,39 @@ public class ForgeInstaller {
         }
     }
 }
+
+/// Split `host[:port]` (IPv6 hosts in brackets) for the legacy `--server`/`--port` flags
+fn split_server_address(address: &str) -> (String, String) {
+    let address = address.trim();
+    if let Some(rest) = address.strip_prefix('[') {
+        if let Some((host, tail)) = rest.split_once(']') {
+            let port = tail.strip_prefix(':').filter(|port| !port.is_empty()).unwrap_or("25565");
+            return (host.to_string(), port.to_string());
+        }
+    }
+    match address.split_once(':') {
+        Some((host, port)) if !port.contains(':') && !port.is_empty() => (host.to_string(), port.to_string()),
+        _ => (address.to_string(), "25565".to_string()),
+    }
+}
+
+/// A hook runs through the system shell in the game directory, with the launch's
+/// environment plus a few `MC_*` variables describing it
+fn hook_command(hook: &str, plan: &LaunchPlan) -> std::process::Command {
+    let mut command = if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.arg("/C").arg(hook);
+        command
+    } else {
+        let mut command = std::process::Command::new("sh");
+        command.arg("-c").arg(hook);
+        command
+    };
+    command
+        .current_dir(&plan.working_dir)
+        .envs(&plan.env)
+        .env("MC_VERSION", &plan.version)
+        .env("MC_GAME_DIR", &plan.working_dir)
+        .env("MC_JAVA", &plan.java_path);
+    command
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use crate::error::{LauncherError, LauncherResult};
+use crate::launcher::MinecraftLauncher;
+
+const BMCLAPI_ROOT: &str = "https://bmclapi2.bangbang93.com/";
+
+/// Replace the start of a URL: `https://libraries.minecraft.net/` -> `http://cache.lan/libraries/`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlRewrite {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Mirror {
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub rewrites: Vec<UrlRewrite>,
+}
+
+impl Mirror {
+    /// BMCLAPI mirrors Mojang's metadata, libraries and assets and the loader mavens
+    pub fn bmclapi() -> Self {
+        let maven = format!("{}maven/", BMCLAPI_ROOT);
+        let rewrites = [
+            ("https://piston-meta.mojang.com/", BMCLAPI_ROOT.to_string()),
+            ("https://piston-data.mojang.com/", BMCLAPI_ROOT.to_string()),
+            ("https://launchermeta.mojang.com/", BMCLAPI_ROOT.to_string()),
+            ("https://launcher.mojang.com/", BMCLAPI_ROOT.to_string()),
+            ("https://libraries.minecraft.net/", maven.clone()),
+            ("https://resources.download.minecraft.net/", format!("{}assets/", BMCLAPI_ROOT)),
+            ("https://maven.minecraftforge.net/", maven.clone()),
+            ("https://maven.neoforged.net/releases/", maven.clone()),
+            ("https://maven.fabricmc.net/", maven.clone()),
+            ("https://maven.quiltmc.org/repository/release/", maven),
+        ];
+
+        Self {
+            name: "BMCLAPI".to_string(),
+            enabled: true,
+            rewrites: rewrites.into_iter()
+                .map(|(from, to)| UrlRewrite { from: from.to_string(), to })
+                .collect(),
+        }
+    }
+
+    fn rewrite(&self, url: &str) -> Option<String> {
+        self.rewrites.iter()
+            .find(|rewrite| url.starts_with(&rewrite.from))
+            .map(|rewrite| format!("{}{}", rewrite.to, &url[rewrite.from.len()..]))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadSettings {
+    /// Tried in order before the official servers
+    #[serde(default)]
+    pub mirrors: Vec<Mirror>,
+    /// Use the official URL when every mirror failed
+    #[serde(default = "default_true")]
+    pub fallback_to_official: bool,
+    /// Attempts per URL for transient failures (timeouts, 5xx, 429)
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// Files downloaded at the same time across the whole launcher
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent_downloads: usize,
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self {
+            mirrors: Vec::new(),
+            fallback_to_official: true,
+            max_attempts: default_max_attempts(),
+            max_concurrent_downloads: default_max_concurrent(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_attempts() -> u32 {
+    4
+}
+
+fn default_max_concurrent() -> usize {
+    16
+}
+
+impl DownloadSettings {
+    fn path() -> PathBuf {
+        MinecraftLauncher::get_launcher_data_dir().join("download_settings.json")
+    }
+
+    /// Saved settings, or the defaults (official servers only) when there are none
+    pub fn load() -> Self {
+        std::fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| match serde_json::from_str(&content) {
+                Ok(settings) => Some(settings),
+                Err(e) => {
+                    println!("[Mirrors] Ignoring invalid download settings: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> LauncherResult<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| LauncherError::io_at(parent, format!("Failed to create directory: {}", e)))?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, json).map_err(|e| LauncherError::io_at(&path, format!("Failed to save download settings: {}", e)))
+    }
+
+    /// Every URL `url` may be downloaded from, mirrors first
+    pub fn candidates(&self, url: &str) -> Vec<String> {
+        let mut urls: Vec<String> = self.mirrors.iter()
+            .filter(|mirror| mirror.enabled)
+            .filter_map(|mirror| mirror.rewrite(url))
+            .collect();
+        urls.dedup();
+
+        if urls.is_empty() || self.fallback_to_official {
+            urls.push(url.to_string());
+        }
+        urls
+    }
+}
@@ -2,9 +2,22 @@ pub mod minecraft_launcher;
 pub mod version_manager;
 pub mod version_details;
 pub mod asset_manager;
+pub mod console;
+pub mod content_store;
+pub mod downloader;
+pub mod game_log;
+pub mod download_manager;
 pub mod library_manager;
+pub mod log4j;
 pub mod java_detector;
+pub mod integrity;
+pub mod launch_plan;
+pub mod launch_task;
+pub mod mirrors;
+pub mod process_registry;
+pub mod shell_words;
+pub mod yggdrasil_server;
 
-pub use minecraft_launcher::{MinecraftLauncher, LaunchOptions, LaunchResult};
+pub use minecraft_launcher::{MinecraftLauncher, LaunchOptions, LaunchCustomization, LaunchResult, AuthData, AuthlibInjectorConfig};
 pub use version_manager::{VersionManager, MinecraftVersion};
 pub use version_details::VersionDetails;
@@ -0,0 +1,240 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::{Child, ExitStatus};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::error::{LauncherError, LauncherResult};
+
+/// How often a running game is polled for its exit
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A game started by the launcher that has not exited yet
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningGame {
+    pub instance_id: String,
+    pub pid: u32,
+    pub version: String,
+    pub game_dir: PathBuf,
+    /// Unix timestamp in seconds
+    pub started_at: u64,
+}
+
+struct RunningProcess {
+    info: RunningGame,
+    child: Arc<Mutex<Child>>,
+}
+
+#[derive(Default)]
+struct Registry {
+    /// Several per instance when a launch was forced while the game was running
+    games: HashMap<String, Vec<RunningProcess>>,
+    /// Instances whose launch is still preparing, see `reserve`
+    launching: HashSet<String>,
+}
+
+/// Games that are currently running, keyed by instance id. Kept in Tauri state.
+#[derive(Clone, Default)]
+pub struct ProcessRegistry {
+    inner: Arc<Mutex<Registry>>,
+}
+
+/// Holds an instance id from the start of a launch until its game is registered.
+/// Dropping it (the launch failed or was cancelled) frees the instance again.
+pub struct LaunchReservation {
+    registry: ProcessRegistry,
+    instance_id: String,
+}
+
+impl LaunchReservation {
+    /// Track the spawned game; the returned handle is shared with whoever waits for it
+    pub fn register(self, info: RunningGame, child: Child) -> Arc<Mutex<Child>> {
+        let child = Arc::new(Mutex::new(child));
+        println!("[ProcessRegistry] {} running (PID {})", info.instance_id, info.pid);
+        let mut registry = self.registry.inner.lock().unwrap();
+        registry.launching.remove(&self.instance_id);
+        registry.games.entry(info.instance_id.clone()).or_default().push(RunningProcess { info, child: child.clone() });
+        child
+    }
+}
+
+impl Drop for LaunchReservation {
+    fn drop(&mut self) {
+        self.registry.inner.lock().unwrap().launching.remove(&self.instance_id);
+    }
+}
+
+impl ProcessRegistry {
+    pub fn list(&self) -> Vec<RunningGame> {
+        let mut games: Vec<RunningGame> = self.inner.lock().unwrap()
+            .games
+            .values()
+            .flatten()
+            .map(|process| process.info.clone())
+            .collect();
+        games.sort_by_key(|game| game.started_at);
+        games
+    }
+
+    pub fn is_running(&self, instance_id: &str) -> bool {
+        self.inner.lock().unwrap().games.contains_key(instance_id)
+    }
+
+    /// True while a launch of the instance is still preparing
+    pub fn is_launching(&self, instance_id: &str) -> bool {
+        self.inner.lock().unwrap().launching.contains(instance_id)
+    }
+
+    /// Claim the instance for a launch. Fails when another launch of it is still preparing,
+    /// or when its game is running and `force` is not set.
+    pub fn reserve(&self, instance_id: &str, force: bool) -> LauncherResult<LaunchReservation> {
+        let mut registry = self.inner.lock().unwrap();
+        if registry.launching.contains(instance_id) {
+            return Err(LauncherError::instance(Some(instance_id), "This instance is already being launched"));
+        }
+        if !force && registry.games.contains_key(instance_id) {
+            return Err(LauncherError::instance(Some(instance_id), "This instance is already running"));
+        }
+        registry.launching.insert(instance_id.to_string());
+        Ok(LaunchReservation { registry: self.clone(), instance_id: instance_id.to_string() })
+    }
+
+    /// Forget a game once it has exited. Other games of the same instance (forced launches)
+    /// stay registered.
+    pub fn unregister(&self, instance_id: &str, pid: u32) {
+        let games = &mut self.inner.lock().unwrap().games;
+        if let Some(processes) = games.get_mut(instance_id) {
+            processes.retain(|process| process.info.pid != pid);
+            if processes.is_empty() {
+                games.remove(instance_id);
+            }
+        }
+    }
+
+    /// Ask every game of the instance to close, and kill those still running after `timeout`.
+    /// Returns false when the instance was not running.
+    pub async fn stop(&self, instance_id: &str, timeout: Duration) -> LauncherResult<bool> {
+        let processes: Vec<(u32, Arc<Mutex<Child>>)> = match self.inner.lock().unwrap().games.get(instance_id) {
+            Some(processes) => processes.iter().map(|process| (process.info.pid, process.child.clone())).collect(),
+            None => return Ok(false),
+        };
+        futures::future::try_join_all(
+            processes.into_iter().map(|(pid, child)| self.stop_process(instance_id, pid, child, timeout))
+        ).await?;
+        Ok(true)
+    }
+
+    async fn stop_process(&self, instance_id: &str, pid: u32, child: Arc<Mutex<Child>>, timeout: Duration) -> LauncherResult<()> {
+        println!("[ProcessRegistry] Stopping {} (PID {})", instance_id, pid);
+        if let Err(e) = request_close(pid) {
+            println!("[ProcessRegistry] Graceful stop failed ({}), killing", e);
+        } else {
+            let deadline = Instant::now() + timeout;
+            while Instant::now() < deadline {
+                if has_exited(&child) {
+                    self.unregister(instance_id, pid);
+                    return Ok(());
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+            println!("[ProcessRegistry] {} did not exit after {}s, killing", instance_id, timeout.as_secs());
+        }
+
+        let killed = child.lock().unwrap().kill();
+        // Already exited in the meantime is fine too
+        if let Err(e) = killed {
+            if !has_exited(&child) {
+                return Err(LauncherError::Other(format!("Failed to kill game process {}: {}", pid, e)));
+            }
+        }
+        self.unregister(instance_id, pid);
+        Ok(())
+    }
+}
+
+/// Block until the game exits. Polls instead of `Child::wait` so the lock stays free for `stop`.
+pub fn wait(child: &Arc<Mutex<Child>>) -> std::io::Result<ExitStatus> {
+    loop {
+        if let Some(status) = child.lock().unwrap().try_wait()? {
+            return Ok(status);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn has_exited(child: &Arc<Mutex<Child>>) -> bool {
+    !matches!(child.lock().unwrap().try_wait(), Ok(None))
+}
+
+/// SIGTERM on Unix (the JVM runs its shutdown hooks); a window close request on Windows
+fn request_close(pid: u32) -> std::io::Result<()> {
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+        let mut command = std::process::Command::new("taskkill");
+        command.arg("/PID").arg(pid.to_string());
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        command
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut command = {
+        let mut command = std::process::Command::new("kill");
+        command.arg("-TERM").arg(pid.to_string());
+        command
+    };
+
+    let status = command.status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!("{:?} exited with {}", command.get_program(), status)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reservation_blocks_a_second_launch_until_dropped() {
+        let registry = ProcessRegistry::default();
+        let reservation = registry.reserve("survival", false).unwrap();
+        assert!(registry.is_launching("survival"));
+
+        // Forcing only skips the running check, two launches never prepare at once
+        let err = registry.reserve("survival", true).err().expect("second launch rejected");
+        assert_eq!(err.code(), "INSTANCE_ERROR");
+        assert!(registry.reserve("creative", false).is_ok());
+
+        drop(reservation);
+        assert!(!registry.is_launching("survival"));
+        assert!(registry.reserve("survival", false).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stop_closes_every_game_of_a_forced_relaunch() {
+        let registry = ProcessRegistry::default();
+        let mut children = Vec::new();
+        for force in [false, true] {
+            let child = std::process::Command::new("sleep").arg("30").spawn().unwrap();
+            let info = RunningGame {
+                instance_id: "survival".into(),
+                pid: child.id(),
+                version: "1.20.1".into(),
+                game_dir: PathBuf::from("survival"),
+                started_at: 0,
+            };
+            children.push(registry.reserve("survival", force).unwrap().register(info, child));
+        }
+        assert_eq!(registry.list().len(), 2);
+        // What delete_instance checks before removing the folder
+        assert!(registry.is_running("survival"));
+
+        assert!(registry.stop("survival", Duration::from_secs(5)).await.unwrap());
+        assert!(!registry.is_running("survival"));
+        assert!(children.iter().all(has_exited));
+    }
+}
+
@@ -0,0 +1,103 @@
+use crate::error::{LauncherError, LauncherResult};
+
+/// Split a command line the way a POSIX shell would, without expanding anything:
+/// `-Dfoo="a b" 'c d' e\ f` -> `-Dfoo=a b`, `c d`, `e f`.
+/// On Windows a backslash is a path separator, so it only escapes `"` inside double quotes.
+pub fn split(input: &str) -> LauncherResult<Vec<String>> {
+    split_words(input, cfg!(windows))
+}
+
+fn split_words(input: &str, windows: bool) -> LauncherResult<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // Distinguishes `''` (an empty argument) from no argument at all
+    let mut in_word = false;
+    let mut chars = input.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            '\'' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(unterminated(input, "'")),
+                    }
+                }
+            }
+            '"' => {
+                in_word = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') if windows => {
+                            if let Some(rest) = chars.as_str().strip_prefix('"') {
+                                chars = rest.chars();
+                                word.push('"');
+                            } else {
+                                word.push('\\');
+                            }
+                        }
+                        // Inside double quotes a backslash only escapes these
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(unterminated(input, "\"")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(unterminated(input, "\"")),
+                    }
+                }
+            }
+            '\\' if !windows => {
+                in_word = true;
+                match chars.next() {
+                    Some('\n') => {}
+                    Some(c) => word.push(c),
+                    None => word.push('\\'),
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+fn unterminated(input: &str, quote: &str) -> LauncherError {
+    LauncherError::parse(format!("Unterminated {} quote in: {}", quote, input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn windows_paths_keep_their_backslashes() {
+        let args = split_words(r#"-Dlog=C:\Games\logs "-Djava.home=C:\Program Files\Java" "say \"hi\"""#, true).unwrap();
+        assert_eq!(args, [r"-Dlog=C:\Games\logs", r"-Djava.home=C:\Program Files\Java", r#"say "hi""#]);
+    }
+
+    #[test]
+    fn posix_backslashes_escape() {
+        let args = split_words(r#"a\ b "c\"d" e\f"#, false).unwrap();
+        assert_eq!(args, ["a b", r#"c"d"#, "ef"]);
+    }
+}
@@ -18,6 +18,33 @@ pub struct VersionDetails {
     #[serde(rename = "minecraftArguments", default)]
     pub minecraft_arguments: Option<String>,
     pub arguments: Option<Arguments>,
+    #[serde(default)]
+    pub logging: Option<Logging>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Logging {
+    #[serde(default)]
+    pub client: Option<LoggingConfig>,
+}
+
+/// Log4j2 configuration the official launcher passes to the game
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LoggingConfig {
+    /// JVM argument with a `${path}` placeholder for the downloaded file
+    pub argument: String,
+    pub file: LoggingFile,
+    /// `log4j2-xml`
+    #[serde(rename = "type")]
+    pub config_type: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LoggingFile {
+    pub id: String,
+    pub sha1: String,
+    pub size: u64,
+    pub url: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -1,8 +1,14 @@
 use serde::{Deserialize, Serialize};
-use reqwest;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::error::{LauncherError, LauncherResult};
+use crate::launcher::downloader::Downloader;
+use crate::launcher::integrity::sha1_hex;
+use crate::launcher::VersionDetails;
 
 const VERSION_MANIFEST_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+/// How long the cached manifest is trusted before asking Mojang again
+const MANIFEST_TTL: Duration = Duration::from_secs(60 * 60);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MinecraftVersion {
@@ -13,6 +19,9 @@ pub struct MinecraftVersion {
     pub time: String,
     #[serde(rename = "releaseTime")]
     pub release_time: String,
+    /// SHA-1 of the version JSON (only in the v2 manifest)
+    #[serde(default)]
+    pub sha1: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -29,35 +38,70 @@ pub struct LatestVersions {
     pub snapshot: String,
 }
 
-#[allow(dead_code)]
 pub struct VersionManager {
+    /// The `versions` directory: holds the cached manifest and `<id>/<id>.json` files
     cache_dir: PathBuf,
+    downloader: Downloader,
+    /// Off for dry runs: whatever is fetched is used but not written to `cache_dir`
+    write_cache: bool,
 }
 
 impl VersionManager {
     pub fn new(cache_dir: PathBuf) -> Self {
-        Self { cache_dir }
+        Self { cache_dir, downloader: Downloader::new(), write_cache: true }
+    }
+
+    /// Never write to the cache directory (launch planning)
+    pub fn read_only(mut self) -> Self {
+        self.write_cache = false;
+        self
+    }
+
+    fn manifest_cache_path(&self) -> PathBuf {
+        self.cache_dir.join("version_manifest_v2.json")
+    }
+
+    fn version_json_path(&self, id: &str) -> PathBuf {
+        self.cache_dir.join(id).join(format!("{}.json", id))
     }
 
-    /// Fetch version manifest from Mojang
-    pub async fn fetch_version_manifest(&self) -> Result<VersionManifest, String> {
+    /// Version manifest, served from disk while younger than `MANIFEST_TTL`.
+    /// When Mojang can't be reached an outdated copy is used instead of failing.
+    pub async fn fetch_version_manifest(&self) -> LauncherResult<VersionManifest> {
+        let cache_path = self.manifest_cache_path();
+        let cache_age = std::fs::metadata(&cache_path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+
+        if let Some(age) = cache_age {
+            if age < MANIFEST_TTL {
+                if let Ok(manifest) = Self::read_manifest(&cache_path) {
+                    println!("[VersionManager] Using cached version manifest ({}s old)", age.as_secs());
+                    return Ok(manifest);
+                }
+            }
+        }
+
         println!("[VersionManager] Fetching version manifest from Mojang...");
-        
-        let response = reqwest::get(VERSION_MANIFEST_URL)
-            .await
-            .map_err(|e| format!("Failed to fetch version manifest: {}", e))?;
-        
-        let manifest: VersionManifest = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse version manifest: {}", e))?;
-        
-        println!("[VersionManager] Found {} versions", manifest.versions.len());
-        Ok(manifest)
+        match self.downloader.fetch_bytes(VERSION_MANIFEST_URL).await {
+            Ok(bytes) => {
+                let manifest: VersionManifest = serde_json::from_slice(&bytes)
+                    .map_err(|e| LauncherError::parse(format!("Failed to parse version manifest: {}", e)))?;
+                self.write_cache(&cache_path, &bytes);
+                println!("[VersionManager] Found {} versions", manifest.versions.len());
+                Ok(manifest)
+            }
+            Err(e) if cache_path.exists() => {
+                println!("[VersionManager] {} - using cached manifest", e);
+                Self::read_manifest(&cache_path)
+            }
+            Err(e) => Err(e),
+        }
     }
 
     /// Get list of release versions only
-    pub async fn get_release_versions(&self) -> Result<Vec<MinecraftVersion>, String> {
+    pub async fn get_release_versions(&self) -> LauncherResult<Vec<MinecraftVersion>> {
         let manifest = self.fetch_version_manifest().await?;
         Ok(manifest
             .versions
@@ -68,23 +112,98 @@ impl VersionManager {
 
     /// Get all versions (releases and snapshots)
     #[allow(dead_code)]
-    pub async fn get_all_versions(&self) -> Result<Vec<MinecraftVersion>, String> {
+    pub async fn get_all_versions(&self) -> LauncherResult<Vec<MinecraftVersion>> {
         let manifest = self.fetch_version_manifest().await?;
         Ok(manifest.versions)
     }
 
-    pub async fn fetch_version_details(&self, url: &str) -> Result<crate::launcher::VersionDetails, String> {
-        println!("[VersionManager] Fetching version details from {}...", url);
-        
-        let response = reqwest::get(url)
-            .await
-            .map_err(|e| format!("Failed to fetch version details: {}", e))?;
-        
-        let details: crate::launcher::VersionDetails = response
-            .json()
-            .await
-            .map_err(|e| format!("Failed to parse version details: {}", e))?;
-            
+    /// Version JSON for a manifest entry, cached as `versions/<id>/<id>.json`.
+    /// The cached file is reused as long as it matches the manifest's sha1.
+    pub async fn fetch_version_details(&self, version: &MinecraftVersion) -> LauncherResult<VersionDetails> {
+        let path = self.version_json_path(&version.id);
+
+        if let Ok(bytes) = std::fs::read(&path) {
+            match &version.sha1 {
+                Some(expected) if &sha1_hex(&bytes) != expected => {
+                    println!("[VersionManager] Cached {} does not match the manifest, downloading again", version.id);
+                }
+                _ => {
+                    println!("[VersionManager] Using cached version details for {}", version.id);
+                    return Self::parse_details(&bytes);
+                }
+            }
+        }
+
+        println!("[VersionManager] Fetching version details from {}...", version.url);
+        let bytes = match self.downloader.fetch_bytes(&version.url).await {
+            Ok(bytes) => bytes,
+            Err(e) if path.exists() => {
+                // Outdated, but still the version the user installed
+                println!("[VersionManager] {} - using cached version details", e);
+                return Self::parse_details(&std::fs::read(&path)?);
+            }
+            Err(e) => return Err(e),
+        };
+
+        if let Some(expected) = &version.sha1 {
+            let actual = sha1_hex(&bytes);
+            if &actual != expected {
+                return Err(LauncherError::Checksum { path, expected: expected.clone(), actual });
+            }
+        }
+
+        let details = Self::parse_details(&bytes)?;
+        self.write_cache(&path, &bytes);
         Ok(details)
     }
+
+    /// Resolve a version by id, falling back to an installed version JSON when
+    /// the manifest is unavailable or doesn't list the version
+    pub async fn get_version_details(&self, id: &str) -> LauncherResult<VersionDetails> {
+        match self.fetch_version_manifest().await {
+            Ok(manifest) => {
+                if let Some(version) = manifest.versions.iter().find(|v| v.id == id) {
+                    return self.fetch_version_details(version).await;
+                }
+                println!("[VersionManager] Version {} is not in the manifest, looking for a local copy", id);
+            }
+            Err(e) => println!("[VersionManager] No version manifest available ({}), looking for a local copy", e),
+        }
+
+        self.installed_version_details(id)?
+            .ok_or_else(|| LauncherError::instance(None, format!("Version {} not found", id)))
+    }
+
+    /// The version JSON already in `versions/<id>/`, without asking Mojang
+    pub fn installed_version_details(&self, id: &str) -> LauncherResult<Option<VersionDetails>> {
+        match std::fs::read(self.version_json_path(id)) {
+            Ok(bytes) => Self::parse_details(&bytes).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn read_manifest(path: &Path) -> LauncherResult<VersionManifest> {
+        let bytes = std::fs::read(path)
+            .map_err(|e| LauncherError::io_at(path, format!("Failed to read cached manifest: {}", e)))?;
+        serde_json::from_slice(&bytes)
+            .map_err(|e| LauncherError::parse(format!("Failed to parse version manifest: {}", e)))
+    }
+
+    fn parse_details(bytes: &[u8]) -> LauncherResult<VersionDetails> {
+        serde_json::from_slice(bytes)
+            .map_err(|e| LauncherError::parse(format!("Failed to parse version details: {}", e)))
+    }
+
+    /// Caching is best effort: a failed write only costs a download next time
+    fn write_cache(&self, path: &Path, bytes: &[u8]) {
+        if !self.write_cache {
+            return;
+        }
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Err(e) = std::fs::write(path, bytes) {
+            println!("[VersionManager] Failed to cache {:?}: {}", path, e);
+        }
+    }
 }
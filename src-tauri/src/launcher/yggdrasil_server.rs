@@ -0,0 +1,583 @@
+// Minimal authlib-injector compatible Yggdrasil server for offline accounts.
+// It only listens on localhost and serves profiles, textures and signed properties
+// from the local skin library, so the player's own skin and cape show up without internet.
+//
+// Local-only by design: every launcher runs its own server on a random loopback port and
+// only knows its own profiles. Other machines can neither reach it nor verify against it,
+// so LAN worlds with players from other computers need the host to run in offline mode,
+// and those players' skins are not shown.
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::{DecodePrivateKey, EncodePrivateKey, EncodePublicKey, LineEnding};
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use crate::error::{LauncherError, LauncherResult};
+
+const MAX_REQUEST_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct OfflineProfile {
+    pub uuid: String,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct SkinMeta {
+    #[serde(default)]
+    slim: bool,
+}
+
+/// Skins and capes stored per offline profile under `.porcos/skins/<uuid>/`
+pub struct SkinLibrary {
+    dir: PathBuf,
+}
+
+impl SkinLibrary {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The launcher-wide library in `.porcos/skins`
+    pub fn open_default() -> Self {
+        Self::new(crate::launcher::MinecraftLauncher::get_launcher_data_dir().join("skins"))
+    }
+
+    fn profile_dir(&self, uuid: &str) -> PathBuf {
+        self.dir.join(strip_dashes(uuid))
+    }
+
+    pub fn set_skin(&self, uuid: &str, png_path: &Path, slim: bool) -> LauncherResult<()> {
+        let dir = self.profile_dir(uuid);
+        std::fs::create_dir_all(&dir).map_err(|e| LauncherError::io(format!("Failed to create skin directory: {}", e)))?;
+        copy_png(png_path, &dir.join("skin.png"))?;
+
+        let meta = serde_json::to_string_pretty(&SkinMeta { slim })
+            .map_err(|e| LauncherError::parse(format!("Failed to serialize skin metadata: {}", e)))?;
+        std::fs::write(dir.join("skin.json"), meta).map_err(|e| LauncherError::io(format!("Failed to write skin metadata: {}", e)))
+    }
+
+    pub fn set_cape(&self, uuid: &str, png_path: &Path) -> LauncherResult<()> {
+        let dir = self.profile_dir(uuid);
+        std::fs::create_dir_all(&dir).map_err(|e| LauncherError::io(format!("Failed to create skin directory: {}", e)))?;
+        copy_png(png_path, &dir.join("cape.png"))
+    }
+
+    pub fn clear(&self, uuid: &str) -> LauncherResult<()> {
+        let dir = self.profile_dir(uuid);
+        if dir.exists() {
+            std::fs::remove_dir_all(&dir).map_err(|e| LauncherError::io(format!("Failed to remove skins: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn read_texture(&self, uuid: &str, file: &str) -> Option<Vec<u8>> {
+        std::fs::read(self.profile_dir(uuid).join(file)).ok()
+    }
+
+    fn is_slim(&self, uuid: &str) -> bool {
+        std::fs::read_to_string(self.profile_dir(uuid).join("skin.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<SkinMeta>(&content).ok())
+            .map(|meta| meta.slim)
+            .unwrap_or(false)
+    }
+}
+
+fn copy_png(source: &Path, target: &Path) -> LauncherResult<()> {
+    let bytes = std::fs::read(source).map_err(|e| LauncherError::io(format!("Failed to read image: {}", e)))?;
+    if !bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Err(LauncherError::io_at(source, "Textures must be PNG images"));
+    }
+    std::fs::write(target, bytes).map_err(|e| LauncherError::io_at(target, format!("Failed to store texture: {}", e)))
+}
+
+fn strip_dashes(uuid: &str) -> String {
+    uuid.replace('-', "")
+}
+
+fn sha1_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(bytes);
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub struct YggdrasilServer {
+    api_root: String,
+    signing_key: SigningKey<Sha1>,
+    public_key_pem: String,
+    skins: SkinLibrary,
+    profiles: RwLock<HashMap<String, OfflineProfile>>,
+    /// access token -> profile id (without dashes)
+    tokens: RwLock<HashMap<String, String>>,
+    /// profile id -> server id of its last `join`, checked by `hasJoined`
+    joins: RwLock<HashMap<String, String>>,
+}
+
+impl YggdrasilServer {
+    /// Bind to an ephemeral localhost port and start serving in the background.
+    /// Connections from anywhere but loopback are dropped.
+    pub async fn start(data_dir: PathBuf, skins: SkinLibrary) -> LauncherResult<Arc<Self>> {
+        std::fs::create_dir_all(&data_dir).map_err(|e| LauncherError::io(format!("Failed to create Yggdrasil directory: {}", e)))?;
+
+        let key_path = data_dir.join("signing_key.pem");
+        let private_key = tokio::task::spawn_blocking(move || load_or_create_key(&key_path))
+            .await
+            .map_err(|e| LauncherError::Other(format!("Key generation task failed: {}", e)))??;
+
+        let public_key_pem = RsaPublicKey::from(&private_key)
+            .to_public_key_pem(LineEnding::LF)
+            .map_err(|e| LauncherError::Other(format!("Failed to encode public key: {}", e)))?;
+
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| LauncherError::Other(format!("Failed to bind Yggdrasil server: {}", e)))?;
+        let port = listener.local_addr()?.port();
+
+        let server = Arc::new(Self {
+            api_root: format!("http://127.0.0.1:{}", port),
+            signing_key: SigningKey::<Sha1>::new(private_key),
+            public_key_pem,
+            skins,
+            profiles: RwLock::new(HashMap::new()),
+            tokens: RwLock::new(HashMap::new()),
+            joins: RwLock::new(HashMap::new()),
+        });
+
+        println!("[Yggdrasil] Local server listening on {}", server.api_root);
+
+        let accept_server = server.clone();
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((_, peer)) if !peer.ip().is_loopback() => {
+                        println!("[Yggdrasil] Refused connection from {}", peer);
+                    }
+                    Ok((stream, _)) => {
+                        let server = accept_server.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = server.handle_connection(stream).await {
+                                println!("[Yggdrasil] Request failed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => println!("[Yggdrasil] Accept failed: {}", e),
+                }
+            }
+        });
+
+        Ok(server)
+    }
+
+    pub fn api_root(&self) -> &str {
+        &self.api_root
+    }
+
+    pub fn register_profile(&self, profile: OfflineProfile) {
+        let id = strip_dashes(&profile.uuid);
+        self.profiles.write().unwrap().insert(id, profile);
+    }
+
+    /// Create an access token the game can use for `join` requests
+    pub fn issue_token(&self, uuid: &str) -> String {
+        let token = strip_dashes(&uuid::Uuid::new_v4().to_string());
+        self.tokens.write().unwrap().insert(token.clone(), strip_dashes(uuid));
+        token
+    }
+
+    /// `${user_properties}` value (legacy property map) for a profile
+    pub fn user_properties(&self, uuid: &str) -> String {
+        let properties: HashMap<&str, Vec<String>> = self
+            .find_profile_by_id(uuid)
+            .and_then(|profile| self.textures_property(&profile))
+            .map(|value| HashMap::from([("textures", vec![value])]))
+            .unwrap_or_default();
+        serde_json::to_string(&properties).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    fn find_profile_by_id(&self, uuid: &str) -> Option<OfflineProfile> {
+        self.profiles.read().unwrap().get(&strip_dashes(uuid)).cloned()
+    }
+
+    fn find_profile_by_name(&self, name: &str) -> Option<OfflineProfile> {
+        self.profiles
+            .read()
+            .unwrap()
+            .values()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Base64 encoded `textures` property value, or None when the profile has no textures
+    fn textures_property(&self, profile: &OfflineProfile) -> Option<String> {
+        let mut textures = serde_json::Map::new();
+
+        if let Some(skin) = self.skins.read_texture(&profile.uuid, "skin.png") {
+            let mut skin_entry = serde_json::json!({
+                "url": format!("{}/textures/{}", self.api_root, sha1_hex(&skin)),
+            });
+            if self.skins.is_slim(&profile.uuid) {
+                skin_entry["metadata"] = serde_json::json!({ "model": "slim" });
+            }
+            textures.insert("SKIN".to_string(), skin_entry);
+        }
+
+        if let Some(cape) = self.skins.read_texture(&profile.uuid, "cape.png") {
+            textures.insert("CAPE".to_string(), serde_json::json!({
+                "url": format!("{}/textures/{}", self.api_root, sha1_hex(&cape)),
+            }));
+        }
+
+        if textures.is_empty() {
+            return None;
+        }
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        let payload = serde_json::json!({
+            "timestamp": timestamp,
+            "profileId": strip_dashes(&profile.uuid),
+            "profileName": profile.name,
+            "textures": textures,
+        });
+        Some(BASE64.encode(payload.to_string()))
+    }
+
+    fn profile_json(&self, profile: &OfflineProfile, signed: bool) -> serde_json::Value {
+        let mut properties = Vec::new();
+        if let Some(value) = self.textures_property(profile) {
+            let mut property = serde_json::json!({ "name": "textures", "value": value });
+            if signed {
+                let signature = self.signing_key.sign(value.as_bytes());
+                property["signature"] = serde_json::Value::String(BASE64.encode(signature.to_bytes()));
+            }
+            properties.push(property);
+        }
+
+        serde_json::json!({
+            "id": strip_dashes(&profile.uuid),
+            "name": profile.name,
+            "properties": properties,
+        })
+    }
+
+    fn metadata(&self) -> serde_json::Value {
+        serde_json::json!({
+            "meta": {
+                "serverName": "Porcos Local",
+                "implementationName": "porcos-launcher",
+                "implementationVersion": env!("CARGO_PKG_VERSION"),
+                "feature.non_email_login": true,
+            },
+            "skinDomains": ["127.0.0.1", "localhost"],
+            "signaturePublickey": self.public_key_pem,
+        })
+    }
+
+    /// Look up a texture by hash across all registered profiles
+    fn find_texture(&self, hash: &str) -> Option<Vec<u8>> {
+        let profiles: Vec<OfflineProfile> = self.profiles.read().unwrap().values().cloned().collect();
+        for profile in profiles {
+            for file in ["skin.png", "cape.png"] {
+                if let Some(bytes) = self.skins.read_texture(&profile.uuid, file) {
+                    if sha1_hex(&bytes) == hash {
+                        return Some(bytes);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    async fn handle_connection(&self, mut stream: TcpStream) -> LauncherResult<()> {
+        let request = match read_request(&mut stream).await? {
+            Some(request) => request,
+            None => return Ok(()),
+        };
+
+        let response = self.route(&request);
+        stream.write_all(&response).await?;
+        stream.shutdown().await.map_err(LauncherError::from)
+    }
+
+    fn route(&self, request: &HttpRequest) -> Vec<u8> {
+        let path = request.path.trim_end_matches('/');
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap_or(serde_json::Value::Null);
+
+        match (request.method.as_str(), path) {
+            ("GET", "") => json_response(200, &self.metadata()),
+            ("POST", "/authserver/authenticate") => {
+                let username = body["username"].as_str().unwrap_or_default();
+                match self.find_profile_by_name(username) {
+                    Some(profile) => {
+                        let access_token = self.issue_token(&profile.uuid);
+                        let client_token = body["clientToken"].as_str().map(|s| s.to_string())
+                            .unwrap_or_else(|| strip_dashes(&uuid::Uuid::new_v4().to_string()));
+                        let selected = serde_json::json!({ "id": strip_dashes(&profile.uuid), "name": profile.name });
+                        json_response(200, &serde_json::json!({
+                            "accessToken": access_token,
+                            "clientToken": client_token,
+                            "availableProfiles": [selected.clone()],
+                            "selectedProfile": selected,
+                        }))
+                    }
+                    None => error_response(403, "ForbiddenOperationException", "Invalid credentials. Invalid username or password."),
+                }
+            }
+            ("POST", "/authserver/refresh") => {
+                let old_token = body["accessToken"].as_str().unwrap_or_default();
+                let profile_id = self.tokens.write().unwrap().remove(old_token);
+                match profile_id.and_then(|id| self.find_profile_by_id(&id)) {
+                    Some(profile) => {
+                        let access_token = self.issue_token(&profile.uuid);
+                        json_response(200, &serde_json::json!({
+                            "accessToken": access_token,
+                            "clientToken": body["clientToken"],
+                            "selectedProfile": { "id": strip_dashes(&profile.uuid), "name": profile.name },
+                        }))
+                    }
+                    None => error_response(403, "ForbiddenOperationException", "Invalid token."),
+                }
+            }
+            ("POST", "/authserver/validate") => {
+                let token = body["accessToken"].as_str().unwrap_or_default();
+                if self.tokens.read().unwrap().contains_key(token) {
+                    empty_response(204)
+                } else {
+                    error_response(403, "ForbiddenOperationException", "Invalid token.")
+                }
+            }
+            ("POST", "/authserver/invalidate") => {
+                let token = body["accessToken"].as_str().unwrap_or_default();
+                self.tokens.write().unwrap().remove(token);
+                empty_response(204)
+            }
+            ("POST", "/authserver/signout") => empty_response(204),
+            ("POST", "/sessionserver/session/minecraft/join") => {
+                // Only the game holding the token issued for this profile may join as it
+                let profile_id = strip_dashes(body["selectedProfile"].as_str().unwrap_or_default());
+                let token = body["accessToken"].as_str().unwrap_or_default();
+                let server_id = body["serverId"].as_str().unwrap_or_default();
+                let owns_token = self.tokens.read().unwrap().get(token) == Some(&profile_id);
+                if owns_token && self.find_profile_by_id(&profile_id).is_some() {
+                    self.joins.write().unwrap().insert(profile_id, server_id.to_string());
+                    empty_response(204)
+                } else {
+                    error_response(403, "ForbiddenOperationException", "Invalid token.")
+                }
+            }
+            ("GET", "/sessionserver/session/minecraft/hasJoined") => {
+                let username = request.query.get("username").map(|s| s.as_str()).unwrap_or_default();
+                let server_id = request.query.get("serverId").map(|s| s.as_str()).unwrap_or_default();
+                let joined = self.find_profile_by_name(username).filter(|profile| {
+                    self.joins.read().unwrap().get(&strip_dashes(&profile.uuid)).map(String::as_str) == Some(server_id)
+                });
+                match joined {
+                    Some(profile) => json_response(200, &self.profile_json(&profile, true)),
+                    None => empty_response(204),
+                }
+            }
+            ("POST", "/api/profiles/minecraft") => {
+                let names: Vec<String> = serde_json::from_value(body).unwrap_or_default();
+                let found: Vec<serde_json::Value> = names.iter()
+                    .filter_map(|name| self.find_profile_by_name(name))
+                    .map(|profile| serde_json::json!({ "id": strip_dashes(&profile.uuid), "name": profile.name }))
+                    .collect();
+                json_response(200, &serde_json::Value::Array(found))
+            }
+            ("GET", p) if p.starts_with("/sessionserver/session/minecraft/profile/") => {
+                let id = &p["/sessionserver/session/minecraft/profile/".len()..];
+                let signed = request.query.get("unsigned").map(|v| v == "false").unwrap_or(false);
+                match self.find_profile_by_id(id) {
+                    Some(profile) => json_response(200, &self.profile_json(&profile, signed)),
+                    None => empty_response(204),
+                }
+            }
+            ("GET", p) if p.starts_with("/textures/") => {
+                match self.find_texture(&p["/textures/".len()..]) {
+                    Some(bytes) => http_response(200, "image/png", &bytes),
+                    None => error_response(404, "NotFoundException", "Texture not found."),
+                }
+            }
+            _ => error_response(404, "NotFoundException", "Not found."),
+        }
+    }
+}
+
+fn load_or_create_key(path: &Path) -> LauncherResult<RsaPrivateKey> {
+    if let Ok(pem) = std::fs::read_to_string(path) {
+        if let Ok(key) = RsaPrivateKey::from_pkcs8_pem(&pem) {
+            return Ok(key);
+        }
+        println!("[Yggdrasil] Stored signing key is invalid, generating a new one");
+    }
+
+    println!("[Yggdrasil] Generating signing key...");
+    let key = RsaPrivateKey::new(&mut rand::thread_rng(), 2048)
+        .map_err(|e| LauncherError::Other(format!("Failed to generate signing key: {}", e)))?;
+    let pem = key.to_pkcs8_pem(LineEnding::LF)
+        .map_err(|e| LauncherError::Other(format!("Failed to encode signing key: {}", e)))?;
+    std::fs::write(path, pem.as_bytes()).map_err(|e| LauncherError::io(format!("Failed to save signing key: {}", e)))?;
+    Ok(key)
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+async fn read_request(stream: &mut TcpStream) -> LauncherResult<Option<HttpRequest>> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buffer.len() > MAX_REQUEST_SIZE {
+            return Err(LauncherError::network("Request headers too large"));
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or_default().to_string();
+
+    let content_length = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_REQUEST_SIZE);
+
+    let mut body = buffer[header_end..].to_vec();
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+
+    let (path, query_string) = target.split_once('?').unwrap_or((target.as_str(), ""));
+    let query = query_string
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+    Ok(Some(HttpRequest { method, path: path.to_string(), query, body }))
+}
+
+fn http_response(status: u16, content_type: &str, body: &[u8]) -> Vec<u8> {
+    let reason = match status {
+        200 => "OK",
+        204 => "No Content",
+        403 => "Forbidden",
+        _ => "Not Found",
+    };
+    let mut response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status, reason, content_type, body.len()
+    ).into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+fn json_response(status: u16, value: &serde_json::Value) -> Vec<u8> {
+    http_response(status, "application/json; charset=utf-8", value.to_string().as_bytes())
+}
+
+fn empty_response(status: u16) -> Vec<u8> {
+    http_response(status, "text/plain", &[])
+}
+
+fn error_response(status: u16, error: &str, message: &str) -> Vec<u8> {
+    json_response(status, &serde_json::json!({ "error": error, "errorMessage": message }))
+}
+
+/// Lazily started server shared through Tauri state
+#[derive(Default)]
+pub struct YggdrasilState {
+    server: tokio::sync::Mutex<Option<Arc<YggdrasilServer>>>,
+}
+
+impl YggdrasilState {
+    pub async fn get_or_start(&self) -> LauncherResult<Arc<YggdrasilServer>> {
+        let mut server = self.server.lock().await;
+        if let Some(existing) = server.as_ref() {
+            return Ok(existing.clone());
+        }
+
+        let data_dir = crate::launcher::MinecraftLauncher::get_launcher_data_dir().join("yggdrasil");
+        let started = YggdrasilServer::start(data_dir, SkinLibrary::open_default()).await?;
+        *server = Some(started.clone());
+        Ok(started)
+    }
+
+    /// The server if a launch already started it
+    pub async fn running(&self) -> Option<Arc<YggdrasilServer>> {
+        self.server.lock().await.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(path: &str, body: serde_json::Value) -> HttpRequest {
+        HttpRequest { method: "POST".into(), path: path.into(), query: HashMap::new(), body: body.to_string().into_bytes() }
+    }
+
+    fn has_joined(username: &str, server_id: &str) -> HttpRequest {
+        let query = HashMap::from([("username".to_string(), username.to_string()), ("serverId".to_string(), server_id.to_string())]);
+        HttpRequest { method: "GET".into(), path: "/sessionserver/session/minecraft/hasJoined".into(), query, body: Vec::new() }
+    }
+
+    fn status(response: &[u8]) -> String {
+        String::from_utf8_lossy(response).split_whitespace().nth(1).unwrap_or_default().to_string()
+    }
+
+    #[tokio::test]
+    async fn join_needs_the_token_issued_for_the_profile() {
+        let dir = std::env::temp_dir().join(format!("porcos-yggdrasil-{}", uuid::Uuid::new_v4()));
+        let server = YggdrasilServer::start(dir.clone(), SkinLibrary::new(dir.join("skins"))).await.unwrap();
+        let (alex, steve) = ("11111111-1111-1111-1111-111111111111", "22222222-2222-2222-2222-222222222222");
+        server.register_profile(OfflineProfile { uuid: alex.into(), name: "Alex".into() });
+        server.register_profile(OfflineProfile { uuid: steve.into(), name: "Steve".into() });
+        let alex_token = server.issue_token(alex);
+
+        let join = |token: &str, profile: &str| post("/sessionserver/session/minecraft/join", serde_json::json!({
+            "accessToken": token, "selectedProfile": strip_dashes(profile), "serverId": "abc",
+        }));
+        assert_eq!(status(&server.route(&join(&alex_token, steve))), "403");
+        assert_eq!(status(&server.route(&join("made-up", alex))), "403");
+        assert_eq!(status(&server.route(&has_joined("Alex", "abc"))), "204");
+
+        assert_eq!(status(&server.route(&join(&alex_token, alex))), "204");
+        assert_eq!(status(&server.route(&has_joined("Alex", "abc"))), "200");
+        assert_eq!(status(&server.route(&has_joined("Alex", "other"))), "204");
+        assert_eq!(status(&server.route(&has_joined("Steve", "abc"))), "204");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
+
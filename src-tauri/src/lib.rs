@@ -1,4 +1,6 @@
+mod auth;
 mod commands;
+mod error;
 mod launcher;
 
 use std::sync::Mutex;
@@ -22,12 +24,41 @@ pub fn run() {
             frontend_task: false,
             backend_task: false,
         }))
+        .manage(launcher::yggdrasil_server::YggdrasilState::default())
+        .manage(launcher::download_manager::DownloadManager::default())
+        .manage(launcher::launch_task::LaunchRegistry::default())
+        .manage(launcher::process_registry::ProcessRegistry::default())
+        .manage(launcher::console::ConsoleHistory::default())
         .invoke_handler(tauri::generate_handler![
             commands::auth::login_microsoft,
+            commands::auth::login_microsoft_browser,
             commands::auth::login_offline,
+            commands::auth::login_yggdrasil,
+            commands::auth::refresh_session,
+            commands::auth::validate_session,
+            commands::auth::get_session_profile,
             commands::auth::open_url,
+            commands::accounts::list_accounts,
+            commands::accounts::get_active_account,
+            commands::accounts::add_account,
+            commands::accounts::remove_account,
+            commands::accounts::set_active_account,
+            commands::accounts::rename_account,
+            commands::profile::get_minecraft_profile,
+            commands::profile::upload_skin,
+            commands::profile::reset_skin,
+            commands::profile::show_cape,
+            commands::profile::hide_cape,
             commands::launcher::get_available_versions,
             commands::launcher::launch_minecraft,
+            commands::launcher::prepare_launch,
+            commands::launcher::export_launch_script,
+            commands::launcher::verify_version,
+            commands::launcher::cancel_launch,
+            commands::launcher::list_running,
+            commands::launcher::is_running,
+            commands::launcher::stop_instance,
+            commands::launcher::get_console_history,
             commands::launcher::generate_offline_uuid,
             commands::instances::create_instance,
             commands::instances::get_instances,
@@ -35,12 +66,24 @@ pub fn run() {
             commands::instances::delete_instance,
             commands::instances::get_instance_path,
             commands::instances::open_instance_folder,
+            commands::instances::migrate_instance_content,
             commands::modloaders::get_fabric_versions,
             commands::modloaders::get_quilt_versions,
             commands::modloaders::get_forge_versions,
             commands::modloaders::get_neoforge_versions,
             commands::network::fetch_cors,
             commands::network::download_file,
+            commands::network::get_download_settings,
+            commands::network::set_download_settings,
+            commands::network::get_mirror_presets,
+            commands::downloads::list_downloads,
+            commands::downloads::get_download_progress,
+            commands::downloads::pause_download,
+            commands::downloads::resume_download,
+            commands::downloads::cancel_download,
+            commands::downloads::pause_all_downloads,
+            commands::downloads::resume_all_downloads,
+            commands::downloads::cancel_all_downloads,
             commands::files::extract_zip,
             commands::files::read_text_file,
             commands::files::read_binary_file,
@@ -55,9 +98,19 @@ pub fn run() {
             commands::files::rename_file,
             commands::files::remove_dir,
             commands::files::merge_dir,
+            commands::yggdrasil::set_offline_skin,
+            commands::yggdrasil::set_offline_cape,
+            commands::yggdrasil::clear_offline_skin,
+            commands::yggdrasil::get_local_yggdrasil_url,
+            commands::vault::get_vault_status,
+            commands::vault::unlock_vault,
+            commands::vault::lock_vault,
+            commands::vault::rotate_vault_key,
+            commands::vault::wipe_vault,
             set_complete
         ])
         .setup(|app| {
+            app.state::<launcher::download_manager::DownloadManager>().attach(app.handle().clone());
             spawn(setup(app.handle().clone()));
             Ok(())
         })
@@ -111,6 +164,14 @@ async fn set_complete(
 
 async fn setup(app: AppHandle) -> Result<(), ()> {
     println!("Performing backend setup task...");
+    // Instances used to keep their own copy of versions, libraries and assets
+    let migration_app = app.clone();
+    spawn(async move {
+        let result = tauri::async_runtime::spawn_blocking(move || commands::instances::migrate_instances_once(&migration_app)).await;
+        if let Ok(Err(e)) = result {
+            println!("Failed to migrate instance content to the shared store: {}", e);
+        }
+    });
     // No delay for immediate splash display
     println!("Backend setup task completed!");
     
@@ -7,6 +7,7 @@ import Instances from '@/pages/Instances';
 import Mods from '@/pages/Mods';
 import React from 'react';
 import { useAuthStore } from '@/stores/authStore';
+import VaultUnlockModal, { VaultStatus } from '@/components/VaultUnlockModal';
 
 interface UserProfile {
   username: string;
@@ -16,17 +17,51 @@ interface UserProfile {
 }
 
 function App() {
-  const { user, isAuthenticated, isTokenValid, logout } = useAuthStore();
+  const { user, isAuthenticated, isTokenValid, setUser, logout } = useAuthStore();
   const [currentView, setCurrentView] = React.useState<'login' | 'app'>('login');
   const [activePage, setActivePage] = React.useState('home');
   const [userProfile, setUserProfile] = React.useState<UserProfile | null>(null);
 
   const [isLoading, setIsLoading] = React.useState(true);
+  const [vaultStatus, setVaultStatus] = React.useState<VaultStatus | null>(null);
+
+  // Ask for the vault passphrase when it can't open by itself: a passphrase vault, or tokens held
+  // in memory because there is no OS keyring. The login itself never waits on this.
+  const checkVault = React.useCallback(async () => {
+    try {
+      const { invoke } = await import("@tauri-apps/api/core");
+      const status = await invoke<VaultStatus>('get_vault_status');
+      const needsPrompt = !status.unlocked && (status.pending > 0 || status.keySource === 'passphrase');
+      setVaultStatus(needsPrompt ? status : null);
+    } catch (e) {
+      console.warn("Failed to read vault status:", e);
+    }
+  }, []);
 
   // Check for existing session on mount
   React.useEffect(() => {
     const checkSession = async () => {
-      if (isAuthenticated && user && isTokenValid()) {
+      if (isAuthenticated && user) {
+        if (user.mode === 'microsoft' && !isTokenValid()) {
+          // Minecraft tokens only last a day; renew through the stored session instead of logging out.
+          // If that fails (no network, revoked session) keep the account: the launch refreshes again
+          // or falls back to offline, and asks for a new login only when it really has to.
+          try {
+            const { invoke } = await import("@tauri-apps/api/core");
+            const result: any = await invoke('refresh_session', { uuid: user.uuid });
+            setUser({
+              ...user,
+              username: result.username || user.username,
+              accessToken: result.access_token,
+              refreshToken: result.refresh_token,
+              expiresAt: Date.now() + (result.expires_in * 1000)
+            });
+            return;
+          } catch (e) {
+            console.warn("Session refresh failed, keeping the stored account:", e);
+          }
+        }
+
         setUserProfile({
           username: user.username,
           uuid: user.uuid,
@@ -34,20 +69,18 @@ function App() {
           skinUrl: user.skinUrl
         });
         setCurrentView('app');
-      } else if (isAuthenticated && user && !isTokenValid()) {
-        // Token expired, logout
-        logout();
-        setCurrentView('login');
+        checkVault();
       }
       setIsLoading(false);
     };
 
     checkSession();
-  }, [isAuthenticated, user, isTokenValid, logout]);
+  }, [isAuthenticated, user, isTokenValid, setUser, checkVault]);
 
   const handleLoginSuccess = (profile: UserProfile) => {
     setUserProfile(profile);
     setCurrentView('app');
+    checkVault();
   };
 
   const handleLogout = () => {
@@ -102,6 +135,12 @@ function App() {
         >
           {renderPage()}
         </MainLayout>
+        <VaultUnlockModal
+          isOpen={vaultStatus !== null}
+          status={vaultStatus}
+          onUnlocked={() => setVaultStatus(null)}
+          onClose={() => setVaultStatus(null)}
+        />
       </div>
     ) : null
   );
@@ -2,7 +2,7 @@ import React, { useState, useEffect } from 'react';
 import { motion, AnimatePresence } from 'framer-motion';
 import { Box, Settings, Plus, Image as ImageIcon, X, Trash2, Check, Play, Cpu, Gamepad2, ChevronDown, ArrowLeft, Package, Download, AlertCircle, Upload } from 'lucide-react';
 import { Instance, useLauncherStore } from '@/stores/launcherStore';
-import { cn } from '@/lib/utils';
+import { cn, errorMessage } from '@/lib/utils';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 import styles from './InstanceSettings.module.css';
@@ -254,7 +254,7 @@ const InstanceSettings: React.FC<InstanceSettingsProps> = ({ instance: initialIn
         } catch (e) {
             console.error("Failed to delete instance", e);
             setToastType('error');
-            setToastMessage(`Error al eliminar: ${e}`);
+            setToastMessage(`Error al eliminar: ${errorMessage(e)}`);
             setTimeout(() => setToastMessage(null), 5000);
         } finally {
             setIsDeleting(false);
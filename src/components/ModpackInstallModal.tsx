@@ -7,6 +7,7 @@ import { listen } from '@tauri-apps/api/event';
 import { join, appCacheDir, homeDir } from '@tauri-apps/api/path';
 import { useLauncherStore } from '@/stores/launcherStore';
 import styles from './ModpackInstallModal.module.css';
+import { errorMessage } from '@/lib/utils';
 
 // Force re-render
 const TIMESTAMP = Date.now();
@@ -1170,12 +1171,12 @@ const ModpackInstallModal: React.FC<ModpackInstallModalProps> = ({ isOpen, onClo
             }, 1000);
 
         } catch (e: any) {
-            if (e.message === "CANCELLED" || abortRef.current) {
+            if (e?.message === "CANCELLED" || e?.code === "CANCELLED" || abortRef.current) {
                 console.log("Installation cancelled by user");
                 await performCleanup();
             } else {
                 console.error("Installation failed", e);
-                setError(e.message || "Error desconocido durante la instalación");
+                setError(errorMessage(e, "Error desconocido durante la instalación"));
                 setIsInstalling(false);
             }
         }
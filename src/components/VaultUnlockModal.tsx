@@ -0,0 +1,118 @@
+import React from 'react';
+import { KeyRound } from 'lucide-react';
+import { motion } from 'framer-motion';
+import styles from './VaultUnlockModal.module.css';
+import { errorMessage } from '@/lib/utils';
+
+export interface VaultStatus {
+    initialized: boolean;
+    unlocked: boolean;
+    keySource: 'keyring' | 'passphrase' | null;
+    version: number;
+    pending: number;
+}
+
+interface VaultUnlockModalProps {
+    isOpen: boolean;
+    status: VaultStatus | null;
+    onUnlocked: (status: VaultStatus) => void;
+    onClose: () => void;
+}
+
+// Shown when the credential vault can't be opened on its own: a passphrase vault after startup,
+// or no OS keyring available (the session tokens are kept in memory until a passphrase is set).
+const VaultUnlockModal: React.FC<VaultUnlockModalProps> = ({ isOpen, status, onUnlocked, onClose }) => {
+    const [passphrase, setPassphrase] = React.useState('');
+    const [confirm, setConfirm] = React.useState('');
+    const [error, setError] = React.useState<string | null>(null);
+    const [isBusy, setIsBusy] = React.useState(false);
+
+    if (!isOpen || !status) return null;
+
+    const creating = !status.initialized;
+    const keyringVault = status.initialized && status.keySource === 'keyring';
+
+    const handleSubmit = async (e: React.FormEvent) => {
+        e.preventDefault();
+        if (creating && passphrase !== confirm) {
+            setError('Las contraseñas no coinciden');
+            return;
+        }
+
+        setIsBusy(true);
+        setError(null);
+        try {
+            const { invoke } = await import("@tauri-apps/api/core");
+            const result = await invoke<VaultStatus>('unlock_vault', { passphrase });
+            setPassphrase('');
+            setConfirm('');
+            onUnlocked(result);
+        } catch (err) {
+            setError(errorMessage(err));
+        } finally {
+            setIsBusy(false);
+        }
+    };
+
+    return (
+        <div className={styles.overlay}>
+            <motion.div
+                initial={{ scale: 0.95, opacity: 0, y: 10 }}
+                animate={{ scale: 1, opacity: 1, y: 0 }}
+                exit={{ scale: 0.95, opacity: 0, y: 10 }}
+                transition={{ duration: 0.2, ease: "easeOut" }}
+                className={styles.modal}
+            >
+                <div className={styles.header}>
+                    <h3 className={styles.title}>
+                        <KeyRound className={styles.titleIcon} size={24} />
+                        {creating ? 'Protege tus credenciales' : 'Desbloquear credenciales'}
+                    </h3>
+                    <p className={styles.description}>
+                        {keyringVault
+                            ? 'El llavero del sistema no está disponible, así que no se pueden leer las credenciales guardadas. La sesión actual seguirá funcionando hasta que cierres el launcher.'
+                            : creating
+                                ? 'El llavero del sistema no está disponible. Elige una contraseña para guardar tu sesión cifrada en este equipo.'
+                                : 'Introduce la contraseña de tu bóveda para usar las sesiones guardadas.'}
+                    </p>
+                </div>
+
+                {!keyringVault && (
+                    <form id="vault-unlock-form" onSubmit={handleSubmit} className={styles.content}>
+                        <input
+                            type="password"
+                            autoFocus
+                            placeholder="Contraseña"
+                            value={passphrase}
+                            onChange={(e) => setPassphrase(e.target.value)}
+                            className={styles.input}
+                        />
+                        {creating && (
+                            <input
+                                type="password"
+                                placeholder="Repite la contraseña"
+                                value={confirm}
+                                onChange={(e) => setConfirm(e.target.value)}
+                                className={styles.input}
+                            />
+                        )}
+                        {error && <p className={styles.error}>{error}</p>}
+                    </form>
+                )}
+
+                <div className={styles.footer}>
+                    {!keyringVault && (
+                        <button type="submit" form="vault-unlock-form" disabled={isBusy || !passphrase} className={styles.button}>
+                            {isBusy ? 'Desbloqueando...' : creating ? 'Guardar contraseña' : 'Desbloquear'}
+                        </button>
+                    )}
+                    <button onClick={onClose} className={styles.secondaryButton}>
+                        {keyringVault ? 'Entendido' : 'Más tarde'}
+                    </button>
+                </div>
+            </motion.div>
+        </div>
+    );
+};
+
+export default VaultUnlockModal;
@@ -4,3 +4,21 @@ import { twMerge } from "tailwind-merge"
 export function cn(...inputs: ClassValue[]) {
     return twMerge(clsx(inputs))
 }
+
+// Shape of every error returned by a Tauri command (LauncherError in src-tauri/src/error.rs)
+export interface LauncherError {
+    code: string
+    message: string
+    context: Record<string, unknown>
+}
+
+export function isLauncherError(error: unknown): error is LauncherError {
+    return typeof error === 'object' && error !== null && 'code' in error && 'message' in error
+}
+
+// Human readable message for anything caught from `invoke` or thrown in the frontend
+export function errorMessage(error: unknown, fallback = 'Error desconocido'): string {
+    if (isLauncherError(error) || error instanceof Error) return error.message || fallback
+    if (typeof error === 'string') return error || fallback
+    return fallback
+}
@@ -1,7 +1,7 @@
 import React from 'react';
 import { motion } from 'framer-motion';
 import { Loader2, Play } from 'lucide-react';
-import { cn } from '@/lib/utils';
+import { cn, errorMessage } from '@/lib/utils';
 import { useLauncherStore } from '@/stores/launcherStore';
 import { useAuthStore } from '@/stores/authStore';
 
@@ -37,7 +37,7 @@ const Dashboard: React.FC = () => {
             }
         } catch (error) {
             console.error("Failed to load versions:", error);
-            addLog(`Error loading versions: ${error}`);
+            addLog(`Error loading versions: ${errorMessage(error)}`);
         }
     };
 
@@ -85,7 +85,7 @@ const Dashboard: React.FC = () => {
 
         } catch (error) {
             console.error("Launch failed:", error);
-            addLog(`Launch failed: ${error}`);
+            addLog(`Launch failed: ${errorMessage(error)}`);
         } finally {
             setIsLaunching(false);
         }
@@ -3,7 +3,7 @@ import { Box, Settings, Plus, Check, Play, Cpu, Gamepad2, ChevronDown, Package,
 import { motion, AnimatePresence, Reorder } from 'framer-motion';
 import { useLauncherStore, Instance } from '@/stores/launcherStore';
 import { useAuthStore } from '@/stores/authStore';
-import { cn } from '@/lib/utils';
+import { cn, errorMessage } from '@/lib/utils';
 
 
 import styles from './Home.module.css';
@@ -769,7 +769,7 @@ const Home: React.FC = () => {
             }
         } catch (e) {
             console.error("Porcos update check failed", e);
-            addLog(`Update check failed: ${e}`);
+            addLog(`Update check failed: ${errorMessage(e)}`);
         }
 
         const versionString = instance.selectedVersion || instance.version;
@@ -923,11 +923,11 @@ const Home: React.FC = () => {
 
         } catch (error) {
             console.error("Launch failed:", error);
-            addLog(`Launch failed: ${error}`);
+            addLog(`Launch failed: ${errorMessage(error)}`);
             setIsLaunching(false);
             setLaunchStartTime(null);
             setToastType('error');
-            setToastMessage(typeof error === 'string' ? error : "Error al iniciar el juego");
+            setToastMessage(errorMessage(error, "Error al iniciar el juego"));
             setTimeout(() => setToastMessage(null), 5000);
         }
     };
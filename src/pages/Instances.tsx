@@ -3,7 +3,7 @@ import { motion, AnimatePresence } from 'framer-motion';
 import { Plus, Play, Trash2, Loader2, Search, Box, Cpu, ChevronDown, Check, AlertCircle, Settings } from 'lucide-react';
 import { useLauncherStore, Instance } from '@/stores/launcherStore';
 import { useAuthStore } from '@/stores/authStore';
-import { cn } from '@/lib/utils';
+import { cn, errorMessage } from '@/lib/utils';
 import styles from './Instances.module.css';
 import CreateInstanceModal from '@/components/CreateInstanceModal';
 import InstanceDetails from '@/components/InstanceDetails';
@@ -434,7 +434,7 @@ const Instances: React.FC = () => {
         } catch (error) {
             console.error("Failed to delete instance:", error);
             setToastType('error');
-            setToastMessage(`Error al eliminar: ${error}`);
+            setToastMessage(`Error al eliminar: ${errorMessage(error)}`);
             setTimeout(() => setToastMessage(null), 5000);
         } finally {
             setIsDeleting(false);
@@ -491,9 +491,9 @@ const Instances: React.FC = () => {
 
         } catch (error) {
             console.error("Launch failed:", error);
-            addLog(`Launch failed: ${error}`);
+            addLog(`Launch failed: ${errorMessage(error)}`);
             setToastType('error');
-            setToastMessage(typeof error === 'string' ? error : "Error al iniciar el juego");
+            setToastMessage(errorMessage(error, "Error al iniciar el juego"));
             setTimeout(() => setToastMessage(null), 5000);
             setIsLaunching(false);
             setLaunchStartTime(null);